@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+
+use toml::{Table, Value};
+
+use crate::error::{Error, Result};
+use crate::registry::Answers;
+
+/// ANSWERS_FILE is where the known answers to the real puzzle inputs are recorded.
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// KnownAnswers are the recorded answers to each day's real puzzle input.  They're stored as
+/// TOML, with a table for each day, like:
+///
+/// ```toml
+/// [day1]
+/// part1 = "68923"
+/// part2 = "200044"
+/// ```
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct KnownAnswers {
+    days: BTreeMap<u8, Answers>,
+}
+
+impl KnownAnswers {
+    /// parse parses recorded answers.
+    pub fn parse(input: &str) -> Result<Self> {
+        let table: Table = toml::from_str(input).map_err(|err| Error::parse("", err.message()))?;
+        let mut days = BTreeMap::new();
+
+        for (key, value) in table {
+            let day = key.strip_prefix("day")
+                .and_then(|num| num.parse::<u8>().ok())
+                .ok_or_else(|| Error::parse(&key, "expected a table like [day1]"))?;
+
+            let part = |name: &str| match value.get(name) {
+                None => Ok(None),
+                Some(Value::String(answer)) => Ok(Some(answer.clone())),
+                Some(_) => Err(Error::parse(&format!("{}.{}", key, name), "answers must be strings")),
+            };
+
+            days.insert(day, Answers { part1: part("part1")?, part2: part("part2")? });
+        }
+
+        Ok(KnownAnswers { days })
+    }
+
+    /// load reads the answers recorded in the given file.  There are no known answers if the
+    /// file doesn't exist yet.
+    pub fn load(filename: &str) -> Result<Self> {
+        match fs::read_to_string(filename) {
+            Ok(input) => Self::parse(&input).map_err(|err| err.in_file(filename)),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(Error::from(err).in_file(filename)),
+        }
+    }
+
+    /// save writes the answers to the given file.
+    pub fn save(&self, filename: &str) -> Result<()> {
+        fs::write(filename, self.to_string()).map_err(|err| Error::from(err).in_file(filename))
+    }
+
+    /// get returns the recorded answers for the given day.
+    pub fn get(&self, day: u8) -> Option<&Answers> {
+        self.days.get(&day)
+    }
+
+    /// record records the answers for the given day.  Only the parts that were answered are
+    /// replaced, so recording a single part keeps the other part's answer.
+    pub fn record(&mut self, day: u8, answers: &Answers) {
+        let known = self.days.entry(day).or_insert(Answers { part1: None, part2: None });
+
+        if answers.part1.is_some() {
+            known.part1 = answers.part1.clone();
+        }
+
+        if answers.part2.is_some() {
+            known.part2 = answers.part2.clone();
+        }
+    }
+}
+
+impl std::fmt::Display for KnownAnswers {
+    /// Days are written in numeric order, rather than the alphabetical order of their table
+    /// names, so day 10 comes after day 9.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (day, answers)) in self.days.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            writeln!(f, "[day{}]", day)?;
+
+            for (name, answer) in [("part1", &answers.part1), ("part2", &answers.part2)] {
+                if let Some(answer) = answer {
+                    writeln!(f, "{} = {}", name, Value::String(answer.clone()))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// differences returns a description of each part whose answer doesn't match the known answer.
+/// Parts that weren't answered are skipped.
+pub fn differences(known: Option<&Answers>, answers: &Answers) -> Vec<String> {
+    let parts = [
+        (1, known.and_then(|known| known.part1.as_ref()), &answers.part1),
+        (2, known.and_then(|known| known.part2.as_ref()), &answers.part2),
+    ];
+
+    parts.into_iter()
+        .filter_map(|(num, known, answer)| match (known, answer) {
+            (_, None) => None,
+            (None, Some(answer)) => Some(format!("part {}: no known answer, got {:?}", num, answer)),
+            (Some(known), Some(answer)) if known != answer => {
+                Some(format!("part {}: expected {:?}, got {:?}", num, known, answer))
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answers(part1: Option<&str>, part2: Option<&str>) -> Answers {
+        Answers { part1: part1.map(String::from), part2: part2.map(String::from) }
+    }
+
+    #[test]
+    fn test_known_answers() {
+        let mut known = KnownAnswers::parse("[day10]\npart1 = \"13140\"\n\n[day2]\npart2 = \"12\"\n").unwrap();
+        assert_eq!(Some(&answers(Some("13140"), None)), known.get(10));
+        assert_eq!(Some(&answers(None, Some("12"))), known.get(2));
+        assert_eq!(None, known.get(1));
+
+        known.record(2, &answers(Some("15"), None));
+        known.record(10, &answers(None, Some("##..\n..##")));
+
+        let text = known.to_string();
+        assert_eq!("[day2]\npart1 = \"15\"\npart2 = \"12\"\n\n[day10]\npart1 = \"13140\"\npart2 = \"\"\"\n##..\n..##\"\"\"\n", text);
+        assert_eq!(known, KnownAnswers::parse(&text).unwrap());
+
+        assert!(KnownAnswers::parse("[dayone]\npart1 = \"1\"").is_err());
+        assert!(KnownAnswers::parse("[day1]\npart1 = 1").is_err());
+    }
+
+    #[test]
+    fn test_differences() {
+        let known = answers(Some("24000"), Some("45000"));
+
+        assert!(differences(Some(&known), &answers(Some("24000"), Some("45000"))).is_empty());
+        assert!(differences(Some(&known), &answers(None, Some("45000"))).is_empty());
+        assert_eq!(
+            vec!["part 2: expected \"45000\", got \"41000\"".to_string()],
+            differences(Some(&known), &answers(Some("24000"), Some("41000"))));
+        assert_eq!(
+            vec!["part 1: no known answer, got \"24000\"".to_string()],
+            differences(None, &answers(Some("24000"), None)));
+    }
+}
@@ -0,0 +1,112 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::error::Result;
+use crate::registry::Part;
+use crate::solution::Solution;
+
+/// Timings are how long each phase of a puzzle took over every timed run.  A part that wasn't
+/// selected has no timings.
+#[derive(Debug, Default)]
+pub struct Timings {
+    pub parse: Vec<Duration>,
+    pub part1: Vec<Duration>,
+    pub part2: Vec<Duration>,
+}
+
+/// Stats summarize the timings of a phase.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// of returns the stats for the given timings, or None if there aren't any.
+    pub fn of(timings: &[Duration]) -> Option<Self> {
+        let mut sorted = timings.to_vec();
+        sorted.sort();
+
+        Some(Stats {
+            min: *sorted.first()?,
+            median: sorted[sorted.len() / 2],
+            max: *sorted.last()?,
+        })
+    }
+}
+
+/// bench times parsing the puzzle input and answering the selected parts the puzzle has,
+/// separately.  The warm-up runs aren't timed, so caches and the allocator have settled before
+/// the timed runs.
+pub fn bench<S: Solution>(input: &str, part: Part, warmup: usize, runs: usize) -> Result<Timings> {
+    let mut timings = Timings::default();
+
+    for run in 0..warmup + runs {
+        let (parsed, parse) = time(|| S::parse(input));
+        let parsed = parsed?;
+
+        let part1 = (part.one() && S::PARTS.one()).then(|| timed(time(|| S::part1(&parsed)))).transpose()?;
+        let part2 = (part.two() && S::PARTS.two()).then(|| timed(time(|| S::part2(&parsed)))).transpose()?;
+
+        if run >= warmup {
+            timings.parse.push(parse);
+            timings.part1.extend(part1);
+            timings.part2.extend(part2);
+        }
+    }
+
+    Ok(timings)
+}
+
+/// time returns the result of f and how long it took.
+pub(crate) fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = black_box(f());
+
+    (result, start.elapsed())
+}
+
+/// timed returns how long a part took, or its error.
+fn timed<T>((result, elapsed): (Result<T>, Duration)) -> Result<Duration> {
+    result.map(|_| elapsed)
+}
+
+/// WARMUP_RUNS is the number of untimed runs before the timed runs.
+pub const WARMUP_RUNS: usize = 2;
+
+/// HEADER is the heading of the table of timings printed by row.
+pub const HEADER: &str = "Day  Phase           Min       Median          Max";
+
+/// row returns a row of the table of timings for a phase of a day's puzzle.
+pub fn row(day: u8, phase: &str, stats: Stats) -> String {
+    format!("{:>3}  {:<6} {:>12.1?} {:>12.1?} {:>12.1?}", day, phase, stats.min, stats.median, stats.max)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day1::Day1;
+
+    use super::*;
+
+    #[test]
+    fn test_bench() {
+        let input = include_str!("../input/day1_sample.txt");
+
+        let timings = bench::<Day1>(input, Part::Both, 1, 3).unwrap();
+        assert_eq!((3, 3, 3), (timings.parse.len(), timings.part1.len(), timings.part2.len()));
+
+        let timings = bench::<Day1>(input, Part::Two, 0, 2).unwrap();
+        assert_eq!((2, 0, 2), (timings.parse.len(), timings.part1.len(), timings.part2.len()));
+    }
+
+    #[test]
+    fn test_stats() {
+        let millis = |ms: &[u64]| ms.iter().map(|&ms| Duration::from_millis(ms)).collect::<Vec<Duration>>();
+
+        let stats = Stats::of(&millis(&[5, 1, 9, 3, 4])).unwrap();
+        assert_eq!(Stats { min: Duration::from_millis(1), median: Duration::from_millis(4), max: Duration::from_millis(9) }, stats);
+
+        assert_eq!(None, Stats::of(&[]));
+    }
+}
@@ -0,0 +1,311 @@
+use adventofcode_2022::registry::Part;
+
+pub const USAGE: &str = "usage: adventofcode-2022 <day|all> [part] [input] [--format text|json]
+       adventofcode-2022 bench <day|all> [part] [input] [--runs N]
+       adventofcode-2022 verify [day|all] [part] [--record]
+       adventofcode-2022 new <day>
+       adventofcode-2022 fetch <day>
+       adventofcode-2022 extract <day> <page>
+       adventofcode-2022 generate <day> [--size N] [--seed N]
+       adventofcode-2022 stats [input] [--top N]
+
+  day     puzzle day to run, 1-25, or 'all' to run every solved day in parallel
+  part    1 or 2 to run a single part - runs both parts if omitted
+  input   puzzle input file, or '-' to read stdin - defaults to input/dayN.txt
+
+  --format  how to print the answers - json prints a line for each day and part with the
+            answer, its type, how long it took and any error - defaults to text
+
+  bench   time parsing and each part over repeated runs, after a few warm-up runs
+  --runs  number of timed runs for bench - defaults to 10
+
+  verify    check the answers to the real puzzle inputs against answers.toml - verifies
+            every day if no day is given
+  --record  write the answers to answers.toml instead of checking them
+
+  new     start a new day from a template, with empty input files, and register it
+
+  fetch   download a day's puzzle input to input/dayN.txt, unless it's already there - the
+          session token is read from AOC_SESSION or .session, and the site from AOC_BASE_URL
+
+  extract  write the sample input from a saved puzzle page to input/dayN_sample.txt, and fill in
+           the expected answers in the tests new started the day with
+
+  generate  print a random puzzle input for a day, to stress test its solution
+  --size    roughly how many things are in the input, like elves or sensors - defaults to 10
+  --seed    the same seed always generates the same input - defaults to 0
+
+  stats  print statistics of the elves' total calories in a day 1 input, reading it a line at
+         a time so it can be bigger than memory - defaults to input/day1.txt
+  --top  how many of the largest totals to print - defaults to 3";
+
+/// DEFAULT_RUNS is the number of timed runs for bench, if it isn't given.
+const DEFAULT_RUNS: usize = 10;
+
+/// DEFAULT_SIZE is the size of generated inputs, if it isn't given.
+const DEFAULT_SIZE: usize = 10;
+
+/// DEFAULT_TOP is the number of largest totals stats prints, if it isn't given.
+const DEFAULT_TOP: usize = 3;
+
+/// Command is what to do with the selected days.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Command {
+    /// Run prints the answers.
+    Run,
+
+    /// Bench prints how long parsing and each part take over the given number of runs.
+    Bench { runs: usize },
+
+    /// Verify checks the answers to the real puzzle inputs against the known answers,
+    /// or records them as the known answers.
+    Verify { record: bool },
+
+    /// New starts a new day from a template.
+    New,
+
+    /// Fetch downloads a day's puzzle input.
+    Fetch,
+
+    /// Extract takes the sample input and answers from a saved puzzle page.
+    Extract,
+
+    /// Generate prints a random puzzle input of the given size, from the given seed.
+    Generate { size: usize, seed: u64 },
+
+    /// Stats prints statistics of a day 1 input, with the given number of largest totals.
+    Stats { top: usize },
+}
+
+/// Format is how run prints the answers.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Format {
+    /// Text prints the answers for people to read.
+    Text,
+
+    /// Json prints a JSON record for each day and part, one per line.
+    Json,
+}
+
+/// Days selects which days to run.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Days {
+    One(u8),
+    All,
+}
+
+/// Args are the parsed command line arguments.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Args {
+    pub command: Command,
+    pub days: Days,
+    pub part: Part,
+    pub input: Option<String>,
+    pub format: Format,
+}
+
+/// parse_args parses command line arguments (without the program name) that look like
+/// `[bench|verify|new|fetch|extract|generate] <day|all> [part] [input]`, or `stats [input]`.  The part may be
+/// omitted while still passing an input file.
+pub fn parse_args(args: &[String]) -> Result<Args, String> {
+    let mut args = args.iter().map(String::as_str).peekable();
+
+    let mut command = match args.next_if(|&arg| ["bench", "verify", "new", "fetch", "extract", "generate", "stats"].contains(&arg)) {
+        Some("bench") => Command::Bench { runs: DEFAULT_RUNS },
+        Some("verify") => Command::Verify { record: false },
+        Some("new") => Command::New,
+        Some("fetch") => Command::Fetch,
+        Some("extract") => Command::Extract,
+        Some("stats") => Command::Stats { top: DEFAULT_TOP },
+        Some(_) => Command::Generate { size: DEFAULT_SIZE, seed: 0 },
+        None => Command::Run,
+    };
+
+    // verify checks every day unless it's given a day.
+    let verify_all = matches!(command, Command::Verify { .. })
+        && args.peek().is_none_or(|arg| arg.starts_with("--"));
+
+    let days = if verify_all {
+        Days::All
+    } else if let Command::Stats { .. } = command {
+        // stats only reads day 1 inputs.
+        Days::One(1)
+    } else {
+        match args.next() {
+            Some("all") => Days::All,
+            Some(day) => match day.parse::<u8>() {
+                Ok(num) if (1..=25).contains(&num) => Days::One(num),
+                _ => return Err(format!("Invalid day: '{}'", day)),
+            },
+            None => return Err("Missing day".to_string()),
+        }
+    };
+
+    let mut part = Part::Both;
+    let mut input = None;
+    let mut format = Format::Text;
+
+    while let Some(arg) = args.next() {
+        match arg {
+            "--runs" => match (&mut command, args.next().map(str::parse::<usize>)) {
+                (Command::Bench { runs }, Some(Ok(num))) if num > 0 => *runs = num,
+                (Command::Bench { .. }, _) => return Err("--runs must be a positive number".to_string()),
+                _ => return Err("--runs can only be given with bench".to_string()),
+            },
+            "--size" => match (&mut command, args.next().map(str::parse::<usize>)) {
+                (Command::Generate { size, .. }, Some(Ok(num))) => *size = num,
+                (Command::Generate { .. }, _) => return Err("--size must be a number".to_string()),
+                _ => return Err("--size can only be given with generate".to_string()),
+            },
+            "--seed" => match (&mut command, args.next().map(str::parse::<u64>)) {
+                (Command::Generate { seed, .. }, Some(Ok(num))) => *seed = num,
+                (Command::Generate { .. }, _) => return Err("--seed must be a number".to_string()),
+                _ => return Err("--seed can only be given with generate".to_string()),
+            },
+            "--top" => match (&mut command, args.next().map(str::parse::<usize>)) {
+                (Command::Stats { top }, Some(Ok(num))) => *top = num,
+                (Command::Stats { .. }, _) => return Err("--top must be a number".to_string()),
+                _ => return Err("--top can only be given with stats".to_string()),
+            },
+            "--record" => match &mut command {
+                Command::Verify { record } => *record = true,
+                _ => return Err("--record can only be given with verify".to_string()),
+            },
+            "--format" => match (command, args.next()) {
+                (Command::Run, Some("text")) => format = Format::Text,
+                (Command::Run, Some("json")) => format = Format::Json,
+                (Command::Run, _) => return Err("--format must be text or json".to_string()),
+                _ => return Err("--format can only be given when running days".to_string()),
+            },
+            "1" if part == Part::Both && input.is_none() => part = Part::One,
+            "2" if part == Part::Both && input.is_none() => part = Part::Two,
+            _ if input.is_none() => input = Some(arg.to_string()),
+            _ => return Err(format!("Unexpected argument: '{}'", arg)),
+        }
+    }
+
+    if days == Days::All && input.is_some() {
+        return Err("An input file can only be given when running a single day".to_string());
+    }
+
+    if let (Command::Verify { .. }, Some(input)) = (command, &input) {
+        return Err(format!("verify always uses the real puzzle input, not '{}'", input));
+    }
+
+    let single_day = match command {
+        Command::New => Some("new"),
+        Command::Fetch => Some("fetch"),
+        Command::Generate { .. } => Some("generate"),
+        _ => None,
+    };
+
+    if let Some(name) = single_day {
+        if days == Days::All || part != Part::Both || input.is_some() {
+            return Err(format!("{} takes a single day, and nothing else", name));
+        }
+    }
+
+    if matches!(command, Command::Stats { .. }) && part != Part::Both {
+        return Err("stats takes an input file, not a part".to_string());
+    }
+
+    if command == Command::Extract && (days == Days::All || part != Part::Both || input.is_none()) {
+        return Err("extract takes a single day and a saved puzzle page".to_string());
+    }
+
+    Ok(Args { command, days, part, input, format })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Args, String> {
+        parse_args(&args.split_whitespace().map(String::from).collect::<Vec<String>>())
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(Ok(Args { command: Command::Run, days: Days::One(8), part: Part::Both, input: None, format: Format::Text }), parse("8"));
+        assert_eq!(Ok(Args { command: Command::Run, days: Days::One(8), part: Part::Two, input: None, format: Format::Text }), parse("8 2"));
+        assert_eq!(Ok(Args { command: Command::Run, days: Days::All, part: Part::One, input: None, format: Format::Text }), parse("all 1"));
+
+        assert_eq!(
+            Ok(Args { command: Command::Run, days: Days::One(12), part: Part::One, input: Some("input/day12_sample.txt".to_string()), format: Format::Text }),
+            parse("12 1 input/day12_sample.txt"));
+
+        assert_eq!(
+            Ok(Args { command: Command::Run, days: Days::One(12), part: Part::Both, input: Some("input/day12_sample.txt".to_string()), format: Format::Text }),
+            parse("12 input/day12_sample.txt"));
+
+        assert_eq!(
+            Ok(Args { command: Command::Bench { runs: 10 }, days: Days::All, part: Part::Both, input: None, format: Format::Text }),
+            parse("bench all"));
+
+        assert_eq!(
+            Ok(Args { command: Command::Bench { runs: 3 }, days: Days::One(8), part: Part::Two, input: None, format: Format::Text }),
+            parse("bench 8 --runs 3 2"));
+
+        assert_eq!(
+            Ok(Args { command: Command::Verify { record: false }, days: Days::All, part: Part::Both, input: None, format: Format::Text }),
+            parse("verify"));
+
+        assert_eq!(
+            Ok(Args { command: Command::Verify { record: true }, days: Days::One(5), part: Part::One, input: None, format: Format::Text }),
+            parse("verify 5 1 --record"));
+
+        assert_eq!(
+            Ok(Args { command: Command::New, days: Days::One(18), part: Part::Both, input: None, format: Format::Text }),
+            parse("new 18"));
+
+        assert_eq!(
+            Ok(Args { command: Command::Fetch, days: Days::One(18), part: Part::Both, input: None, format: Format::Text }),
+            parse("fetch 18"));
+
+        assert_eq!(
+            Ok(Args { command: Command::Extract, days: Days::One(18), part: Part::Both, input: Some("day18.html".to_string()), format: Format::Text }),
+            parse("extract 18 day18.html"));
+
+        assert_eq!(
+            Ok(Args { command: Command::Generate { size: 1000, seed: 7 }, days: Days::One(15), part: Part::Both, input: None, format: Format::Text }),
+            parse("generate 15 --size 1000 --seed 7"));
+
+        assert_eq!(
+            Ok(Args { command: Command::Run, days: Days::All, part: Part::Both, input: None, format: Format::Json }),
+            parse("all --format json"));
+
+        assert_eq!(
+            Ok(Args { command: Command::Stats { top: 3 }, days: Days::One(1), part: Part::Both, input: None, format: Format::Text }),
+            parse("stats"));
+
+        assert_eq!(
+            Ok(Args { command: Command::Stats { top: 10 }, days: Days::One(1), part: Part::Both, input: Some("-".to_string()), format: Format::Text }),
+            parse("stats - --top 10"));
+    }
+
+    #[test]
+    fn test_parse_args_invalid() {
+        assert!(parse("").is_err());
+        assert!(parse("0").is_err());
+        assert!(parse("26").is_err());
+        assert!(parse("day1").is_err());
+        assert!(parse("all input/day1.txt").is_err());
+        assert!(parse("1 1 input/day1.txt extra").is_err());
+        assert!(parse("1 --runs 3").is_err());
+        assert!(parse("bench 1 --runs 0").is_err());
+        assert!(parse("bench 1 --runs").is_err());
+        assert!(parse("1 --record").is_err());
+        assert!(parse("verify 1 input/day1_sample.txt").is_err());
+        assert!(parse("new all").is_err());
+        assert!(parse("new 18 1").is_err());
+        assert!(parse("fetch all").is_err());
+        assert!(parse("extract 18").is_err());
+        assert!(parse("generate 15 --seed -1").is_err());
+        assert!(parse("1 --size 10").is_err());
+        assert!(parse("1 --format xml").is_err());
+        assert!(parse("bench 1 --format json").is_err());
+        assert!(parse("stats 1").is_err());
+        assert!(parse("stats --top").is_err());
+        assert!(parse("1 --top 3").is_err());
+    }
+}
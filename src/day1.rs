@@ -1,62 +1,264 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::BufRead;
+use std::iter;
+use std::ops::RangeInclusive;
+
 use itertools::Itertools;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let elves = load_elves("input/day1.txt");
+use crate::error::{Error, Result};
+use crate::solution::Solution;
 
-    println!("Day 1");
-    println!("Part 1: {}", most_food(&elves));
-    println!("Part 2: {}", top_three(&elves));
-}
+pub struct Day1;
 
-/// load_elves parses a list of elves and the calories they are carrying from the given file.
-/// Lines list calories, and elves are separated by a blank line.
-fn load_elves(filename: &str) -> Vec<Elf> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
-
-    let mut elves = Vec::new();
-    let mut calories = Vec::new();
-
-    for line in f.lines().flatten() {
-        match (line.as_str(), line.parse::<i32>()) {
-            ("", _) => {
-                elves.push(Elf{ calories });
-                calories = Vec::new();
-            },
-            (_, Ok(num)) => calories.push(num),
-            (_, _) => panic!("Invalid line: {}", line),
-        }
+impl Solution for Day1 {
+    type Input = Vec<Elf>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        load_elves(input)
     }
 
-    if calories.len() > 0 {
-        elves.push(Elf{ calories });
+    fn part1(elves: &Self::Input) -> Result<Self::Part1> {
+        Ok(most_food(elves))
     }
 
-    return elves
+    fn part2(elves: &Self::Input) -> Result<Self::Part2> {
+        Ok(top_three(elves))
+    }
 }
 
-struct Elf {
-    calories: Vec<i32>
+/// load_elves parses a list of elves and the calories they are carrying from the given input.
+/// Lines list calories, and elves are separated by a blank line.
+pub fn load_elves(input: &str) -> Result<Vec<Elf>> {
+    totals(input.as_bytes()).map(|total| total.map(|total| Elf { total })).collect()
+}
+
+pub struct Elf {
+    total: u64
 }
 
 impl Elf {
     /// total_calories returns the total number of calories this elf is carrying.
-    fn total_calories(&self) -> i32 {
-        return self.calories.iter().sum()
+    pub fn total_calories(&self) -> u64 {
+        self.total
     }
 }
 
 /// most_food returns the largest number of calories that an elf is carrying.
-fn most_food(elves: &Vec<Elf>) -> i32 {
+pub fn most_food(elves: &[Elf]) -> u64 {
     elves.iter().map(Elf::total_calories).max().unwrap_or(0)
 }
 
 /// top_three returns the total number of calories held by three elves carrying the most calories.
-fn top_three(elves: &Vec<Elf>) -> i32 {
-    return elves.iter().map(Elf::total_calories).sorted().rev().take(3).sum()
+pub fn top_three(elves: &[Elf]) -> u64 {
+    let mut top = TopK::new(3);
+    elves.iter().for_each(|elf| top.push(elf.total_calories()));
+
+    top.values().iter().sum()
+}
+
+/// totals reads an inventory like load_elves does, but only keeps a running total, so it can
+/// read inventories too big to fit in memory.  It returns each elf's total calories as it's
+/// read, stopping after the first error.
+pub fn totals(reader: impl BufRead) -> impl Iterator<Item = Result<u64>> {
+    let mut lines = reader.lines().enumerate();
+    let mut total = None;
+    let mut failed = false;
+
+    iter::from_fn(move || {
+        while !failed {
+            let Some((i, line)) = lines.next() else {
+                // the last elf doesn't need a blank line after it.
+                return total.take().map(Ok);
+            };
+
+            let line = match line {
+                Ok(line) => line,
+                Err(err) => {
+                    failed = true;
+                    return Some(Err(err.into()));
+                },
+            };
+
+            if line.is_empty() {
+                return Some(Ok(total.take().unwrap_or(0)));
+            }
+
+            match line.parse::<u64>().ok().and_then(|num| total.unwrap_or(0_u64).checked_add(num)) {
+                Some(sum) => total = Some(sum),
+                None => {
+                    failed = true;
+                    return Some(Err(Error::parse(&line, "invalid calories").at_line(i + 1)));
+                },
+            }
+        }
+
+        None
+    })
+}
+
+/// TopK keeps the k largest numbers it's given, in a min-heap so the smallest of them is the
+/// one that's replaced.  It only ever holds k numbers, however many it's given.
+pub struct TopK {
+    k: usize,
+    heap: BinaryHeap<Reverse<u64>>,
+}
+
+impl TopK {
+    /// new returns an empty TopK that keeps the k largest numbers.
+    pub fn new(k: usize) -> Self {
+        TopK { k, heap: BinaryHeap::with_capacity(k + 1) }
+    }
+
+    /// push adds a number, if it's one of the k largest so far.
+    pub fn push(&mut self, num: u64) {
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse(num));
+        } else if self.heap.peek().is_some_and(|&Reverse(smallest)| num > smallest) {
+            self.heap.pop();
+            self.heap.push(Reverse(num));
+        }
+    }
+
+    /// values returns the k largest numbers, largest first.
+    pub fn values(&self) -> Vec<u64> {
+        self.heap.iter().map(|&Reverse(num)| num).sorted().rev().collect()
+    }
+}
+
+/// SUB_BUCKETS is the number of buckets each power of two is split into by a Histogram.
+const SUB_BUCKETS: u64 = 16;
+
+/// Histogram counts numbers in buckets that get wider as the numbers get bigger, so it has a
+/// fixed number of buckets for any u64.  Numbers below SUB_BUCKETS have a bucket each, and
+/// every larger power of two is split into SUB_BUCKETS buckets, so a bucket is never wider
+/// than 1/SUB_BUCKETS of the numbers in it.
+pub struct Histogram {
+    counts: Vec<u64>,
+}
+
+impl Histogram {
+    /// new returns an empty histogram.
+    pub fn new() -> Self {
+        Histogram { counts: vec![0; Self::bucket(u64::MAX) + 1] }
+    }
+
+    /// bucket returns the index of the bucket num is counted in.
+    fn bucket(num: u64) -> usize {
+        if num < SUB_BUCKETS {
+            return num as usize;
+        }
+
+        let power = 63 - num.leading_zeros() as u64;
+        let shift = power - SUB_BUCKETS.trailing_zeros() as u64;
+        ((shift + 1) * SUB_BUCKETS + ((num >> shift) - SUB_BUCKETS)) as usize
+    }
+
+    /// range returns the numbers that are counted in the given bucket.
+    fn range(bucket: usize) -> RangeInclusive<u64> {
+        let bucket = bucket as u64;
+        if bucket < SUB_BUCKETS {
+            return bucket..=bucket;
+        }
+
+        let shift = bucket / SUB_BUCKETS - 1;
+        let start = (SUB_BUCKETS + bucket % SUB_BUCKETS) << shift;
+        start..=start + ((1 << shift) - 1)
+    }
+
+    /// add counts a number.
+    pub fn add(&mut self, num: u64) {
+        self.counts[Self::bucket(num)] += 1;
+    }
+
+    /// buckets returns the range and count of each bucket that has anything in it, in order.
+    pub fn buckets(&self) -> Vec<(RangeInclusive<u64>, u64)> {
+        self.counts.iter().enumerate()
+            .filter(|(_, &count)| count > 0)
+            .map(|(bucket, &count)| (Self::range(bucket), count))
+            .collect()
+    }
+
+    /// percentile returns the range of the bucket the given percentile (0 to 100) falls in,
+    /// using the nearest rank, or None if nothing has been counted.
+    pub fn percentile(&self, percentile: f64) -> Option<RangeInclusive<u64>> {
+        let count = self.counts.iter().sum::<u64>();
+        let rank = ((percentile / 100.0 * count as f64).ceil() as u64).clamp(1, count.max(1));
+
+        let mut seen = 0;
+        self.counts.iter().position(|&bucket| {
+            seen += bucket;
+            seen >= rank
+        }).filter(|_| count > 0).map(Self::range)
+    }
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// CalorieStats are running statistics of elves' total calories, which take the same memory
+/// however many elves there are.  Percentiles come from a histogram, so they're estimates that
+/// are within 1/16 of the real value.
+pub struct CalorieStats {
+    pub count: u64,
+    pub sum: u128,
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+    pub top: TopK,
+    pub histogram: Histogram,
+}
+
+impl CalorieStats {
+    /// new returns empty statistics that keep the top k totals.
+    pub fn new(k: usize) -> Self {
+        CalorieStats { count: 0, sum: 0, min: None, max: None, top: TopK::new(k), histogram: Histogram::new() }
+    }
+
+    /// add adds an elf's total calories.
+    pub fn add(&mut self, total: u64) {
+        self.count += 1;
+        self.sum += total as u128;
+        self.min = Some(self.min.map_or(total, |min| min.min(total)));
+        self.max = Some(self.max.map_or(total, |max| max.max(total)));
+        self.top.push(total);
+        self.histogram.add(total);
+    }
+
+    /// mean returns the mean total, or None if there are no elves.
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum as f64 / self.count as f64)
+    }
+
+    /// percentile returns an estimate of the given percentile (0 to 100) of the totals: the
+    /// middle of the histogram bucket it falls in, kept within the smallest and largest totals.
+    pub fn percentile(&self, percentile: f64) -> Option<u64> {
+        let bucket = self.histogram.percentile(percentile)?;
+        let middle = bucket.start() + (bucket.end() - bucket.start()) / 2;
+
+        Some(middle.clamp(self.min?, self.max?))
+    }
+
+    /// median returns an estimate of the median total.
+    pub fn median(&self) -> Option<u64> {
+        self.percentile(50.0)
+    }
+}
+
+/// analyze reads an inventory in a single pass, and returns statistics of the elves' total
+/// calories that keep the top k totals.
+pub fn analyze(reader: impl BufRead, k: usize) -> Result<CalorieStats> {
+    let mut stats = CalorieStats::new(k);
+
+    for total in totals(reader) {
+        stats.add(total?);
+    }
+
+    Ok(stats)
 }
 
 #[cfg(test)]
@@ -65,13 +267,47 @@ mod tests {
 
     #[test]
     fn test_most_food() {
-        let elves = load_elves("input/day1_sample.txt");
+        let elves = Day1::parse(include_str!("../input/day1_sample.txt")).unwrap();
         assert_eq!(24000, most_food(&elves));
     }
 
     #[test]
     fn test_top_three() {
-        let elves = load_elves("input/day1_sample.txt");
+        let elves = Day1::parse(include_str!("../input/day1_sample.txt")).unwrap();
         assert_eq!(45000, top_three(&elves));
+
+        // an elf's total is checked for overflow however the inventory is read.
+        let input = "18446744073709551615\n1\n";
+        assert_eq!("2: invalid calories: '1'", Day1::parse(input).err().unwrap().to_string());
+        assert_eq!("2: invalid calories: '1'", analyze(input.as_bytes(), 3).err().unwrap().to_string());
+    }
+
+    #[test]
+    fn test_analyze() {
+        let stats = analyze(include_str!("../input/day1_sample.txt").as_bytes(), 3).unwrap();
+        assert_eq!(vec![24000, 11000, 10000], stats.top.values());
+        assert_eq!((5, Some(4000), Some(24000), Some(11000.0)), (stats.count, stats.min, stats.max, stats.mean()));
+
+        // the median is 10000, which is in a bucket that's 512 wide.
+        assert_eq!(Some(9728..=10239), stats.histogram.percentile(50.0));
+        assert_eq!(Some(9983), stats.median());
+        assert_eq!(Some(24000), stats.percentile(100.0));
+        assert_eq!(5, stats.histogram.buckets().iter().map(|(_, count)| count).sum::<u64>());
+
+        // totals past i32::MAX don't overflow.
+        let stats = analyze("4000000000\n4000000000\n\n1\n".as_bytes(), 1).unwrap();
+        assert_eq!(vec![8000000000], stats.top.values());
+
+        let err = analyze("1000\n\nlots\n".as_bytes(), 3).err().unwrap();
+        assert_eq!("3: invalid calories: 'lots'", err.to_string());
+    }
+
+    #[test]
+    fn test_histogram() {
+        for num in [0, 1, 15, 16, 17, 31, 32, 1000, 123456789, u64::MAX] {
+            let range = Histogram::range(Histogram::bucket(num));
+            assert!(range.contains(&num), "{} isn't in {:?}", num, range);
+            assert!(range.end() - range.start() <= num / 16, "{:?} is too wide for {}", range, num);
+        }
     }
 }
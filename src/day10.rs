@@ -1,16 +1,27 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use std::str::FromStr;
-use std::string::ParseError;
-use itertools::Itertools;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let instructions = parse_instructions("input/day10.txt");
+use crate::error::{Error, parse_lines, parse_num, Result};
+use crate::report::Image;
+use crate::solution::Solution;
 
-    println!("Day 10");
-    println!("Part 1: {}", signal_strength(&instructions));
-    println!("Part 2:\n{}", render_image(&instructions));
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Vec<Instruction>;
+    type Part1 = i32;
+    type Part2 = Image;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_instructions(input)
+    }
+
+    fn part1(instructions: &Self::Input) -> Result<Self::Part1> {
+        Ok(signal_strength(instructions))
+    }
+
+    fn part2(instructions: &Self::Input) -> Result<Self::Part2> {
+        Ok(Image(render_image(instructions)))
+    }
 }
 
 struct Computer {
@@ -38,7 +49,7 @@ impl Computer {
     }
 }
 
-enum Instruction {
+pub enum Instruction {
     Noop,
     AddX(i32),
 }
@@ -54,27 +65,26 @@ impl Instruction {
 }
 
 impl FromStr for Instruction {
-    type Err = ParseError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s == "noop" {
-            return Ok(Instruction::Noop)
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        // Instructions look like 'noop' or 'addx -5'
+        match s.split_once(' ') {
+            None if s == "noop" => Ok(Instruction::Noop),
+            Some(("addx", value)) => Ok(Instruction::AddX(parse_num(value)?)),
+            _ => Err(Error::parse(s, "invalid instruction")),
         }
-
-        Ok(Instruction::AddX(s.replace("addx ", "").parse().unwrap()))
     }
 }
 
-fn parse_instructions(filename: &str) -> Vec<Instruction> {
-    let f = File::open(filename);
-    let f = BufReader::new(f.unwrap());
-
-    f.lines().flatten().flat_map(|line| line.parse::<Instruction>()).collect_vec()
+/// parse_instructions parses the CPU instructions, one per line.
+pub fn parse_instructions(input: &str) -> Result<Vec<Instruction>> {
+    parse_lines(input)
 }
 
 /// signal_strength returns the signal strength - cycle number multiplied by the register during
 /// the 20th cycle, and every 40 cycles after that
-fn signal_strength(instructions: &Vec<Instruction>) -> i32 {
+pub fn signal_strength(instructions: &[Instruction]) -> i32 {
     let mut comp = Computer::new();
     let mut ss = 0;
     let mut i = 0;
@@ -84,7 +94,7 @@ fn signal_strength(instructions: &Vec<Instruction>) -> i32 {
             i += 1;
 
             if (i - 20) % 40 == 0 {
-                ss += i as i32 * comp.register;
+                ss += i * comp.register;
             }
         }
 
@@ -95,7 +105,7 @@ fn signal_strength(instructions: &Vec<Instruction>) -> i32 {
 }
 
 /// print_image runs the instructions, printing the image that results.
-fn render_image(instructions: &Vec<Instruction>) -> String {
+pub fn render_image(instructions: &[Instruction]) -> String {
     let mut comp = Computer::new();
     let mut str = String::new();
     let mut i = 0;
@@ -127,14 +137,14 @@ mod tests {
 
     #[test]
     fn test_signal_strength() {
-        let instructions = parse_instructions("input/day10_sample.txt");
+        let instructions = Day10::parse(include_str!("../input/day10_sample.txt")).unwrap();
 
         assert_eq!(13140, signal_strength(&instructions));
     }
 
     #[test]
     fn test_render_image() {
-        let instructions = parse_instructions("input/day10_sample.txt");
+        let instructions = Day10::parse(include_str!("../input/day10_sample.txt")).unwrap();
         let expected = "\
 ##..##..##..##..##..##..##..##..##..##..\n\
 ###...###...###...###...###...###...###.\n\
@@ -1,19 +1,31 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use itertools::Itertools;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let (configs, states) = parse_monkeys("input/day11.txt");
+use crate::error::{Error, parse_num, Result};
+use crate::solution::Solution;
 
-    println!("Day 11");
-    println!("Part 1: {}", monkey_business(&configs, &states, 3, 20));
-    println!("Part 2: {}", monkey_business(&configs, &states, 1, 10_000));
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = (Vec<MonkeyConfig>, Vec<MonkeyState>);
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_monkeys(input)
+    }
+
+    fn part1((configs, states): &Self::Input) -> Result<Self::Part1> {
+        monkey_business(configs, states, 3, 20)
+    }
+
+    fn part2((configs, states): &Self::Input) -> Result<Self::Part2> {
+        monkey_business(configs, states, 1, 10_000)
+    }
 }
 
-/// parse_monkeys parses a list of monkeys out of the given file.
+/// parse_monkeys parses a list of monkeys out of the given input.
 /// A monkey looks like the following, and monkeys are separated by a blank line:
-/// ```
+/// ```text
 /// Monkey 0:
 ///   Starting items: 79, 98
 ///   Operation: new = old * 19
@@ -21,38 +33,48 @@ pub fn solution() {
 ///     If true: throw to monkey 2
 ///     If false: throw to monkey 3
 /// ```
-fn parse_monkeys(filename: &str) -> (Vec<MonkeyConfig>, Vec<MonkeyState>) {
-    let f = File::open(filename);
-    let f = BufReader::new(f.unwrap());
-
-    let mut lines = f.lines().flatten();
+pub fn parse_monkeys(input: &str) -> Result<(Vec<MonkeyConfig>, Vec<MonkeyState>)> {
+    let mut lines = input.lines().enumerate();
     let (mut configs, mut states) = (Vec::new(), Vec::new());
 
+    // Monkeys refer to each other by number - the line they're thrown on, and the monkey number.
+    let mut throws = Vec::new();
+
     loop {
         // First line is 'Monkey 0:', or none if we're done parsing
-        let maybe_monkey = lines.next();
-        if maybe_monkey.is_none() {
-            return (configs, states);
+        match lines.next() {
+            None => break,
+            Some((_, line)) if line.starts_with("Monkey ") => {},
+            Some((i, line)) => return Err(Error::parse(line, "expected 'Monkey'").at_line(i + 1)),
         }
 
         // Next line is 'Starting items: '
-        let holding = lines.next().unwrap()
-            .replace("  Starting items: ", "")
-            .split(", ")
-            .map(|item| item.parse::<u64>().unwrap())
-            .collect_vec();
+        let (line, items) = next_value(&mut lines, "  Starting items: ")?;
+        let holding = items.split(", ")
+            .map(parse_num::<u64>)
+            .collect::<Result<Vec<u64>>>()
+            .map_err(|err| err.at_line(line))?;
 
         // Next line is the operation.
-        let operation = parse_operation(lines.next().unwrap().replace("  Operation: new = ", ""));
+        let (line, operation) = next_value(&mut lines, "  Operation: new = ")?;
+        let operation = parse_operation(operation).map_err(|err| err.at_line(line))?;
 
         // Next line is the Test
-        let test = lines.next().unwrap().replace("  Test: divisible by ", "").parse().unwrap();
+        let (line, test) = next_value(&mut lines, "  Test: divisible by ")?;
+        let test = parse_num::<u64>(test).map_err(|err| err.at_line(line))?;
+        if test == 0 {
+            return Err(Error::parse("0", "can't test divisibility by 0").at_line(line));
+        }
 
         // Next line is the true monkey
-        let true_monkey = lines.next().unwrap().replace("    If true: throw to monkey ", "").parse().unwrap();
+        let (line, true_monkey) = next_value(&mut lines, "    If true: throw to monkey ")?;
+        let true_monkey = parse_num(true_monkey).map_err(|err| err.at_line(line))?;
+        throws.push((line, true_monkey));
 
         // Next line is the false monkey
-        let false_monkey = lines.next().unwrap().replace("    If false: throw to monkey ", "").parse().unwrap();
+        let (line, false_monkey) = next_value(&mut lines, "    If false: throw to monkey ")?;
+        let false_monkey = parse_num(false_monkey).map_err(|err| err.at_line(line))?;
+        throws.push((line, false_monkey));
 
         // Last line for a single monkey is blank
         lines.next();
@@ -60,32 +82,52 @@ fn parse_monkeys(filename: &str) -> (Vec<MonkeyConfig>, Vec<MonkeyState>) {
         configs.push(MonkeyConfig { operation, test, true_monkey, false_monkey });
         states.push(MonkeyState { inspected: 0, holding });
     }
+
+    if let Some((line, monkey)) = throws.into_iter().find(|(_, monkey)| *monkey >= configs.len()) {
+        return Err(Error::parse(&monkey.to_string(), "no such monkey").at_line(line));
+    }
+
+    Ok((configs, states))
+}
+
+/// next_value returns the line number and the rest of the next line after the given prefix.
+fn next_value<'a>(lines: &mut impl Iterator<Item = (usize, &'a str)>, prefix: &str) -> Result<(usize, &'a str)> {
+    let expected = || format!("expected '{}'", prefix.trim());
+
+    match lines.next() {
+        Some((i, line)) => match line.strip_prefix(prefix) {
+            Some(value) => Ok((i + 1, value)),
+            None => Err(Error::parse(line, expected()).at_line(i + 1)),
+        },
+        None => Err(Error::parse("", expected())),
+    }
 }
 
-fn parse_operation(statement: String) -> Box<dyn Fn(u64) -> u64> {
+/// parse_operation parses an operation on a worry level, which returns None if the new worry
+/// level is too big to store.
+fn parse_operation(statement: &str) -> Result<Box<dyn Fn(u64) -> Option<u64>>> {
     // A statement looks like 'old + 8', where the left side is always old, and the right side
     // is either 'old' or a number.  operand can be * or +.
     let parts = statement.split_whitespace().collect_vec();
 
-
-    match (&parts[0..3], parts[2].parse::<u64>()) {
-        (["old", "*", "old"], _) => Box::new(|item| item * item),
-        (["old", "+", "old"], _) => Box::new(|item| item + item),
-        (["old", "*", _], Ok(num)) => Box::new(move |item| item * num),
-        (["old", "+", _], Ok(num)) => Box::new(move |item| item + num),
-        _ => panic!("Unsupported operation: {}", statement)
+    match (parts.as_slice(), parts.get(2).map(|num| num.parse::<u64>())) {
+        (["old", "*", "old"], _) => Ok(Box::new(|item| item.checked_mul(item))),
+        (["old", "+", "old"], _) => Ok(Box::new(|item| item.checked_add(item))),
+        (["old", "*", _], Some(Ok(num))) => Ok(Box::new(move |item| item.checked_mul(num))),
+        (["old", "+", _], Some(Ok(num))) => Ok(Box::new(move |item| item.checked_add(num))),
+        _ => Err(Error::parse(statement, "unsupported operation")),
     }
 }
 
-struct MonkeyConfig {
-    operation: Box<dyn Fn(u64) -> u64>,
+pub struct MonkeyConfig {
+    operation: Box<dyn Fn(u64) -> Option<u64>>,
     test: u64,
     true_monkey: usize,
     false_monkey: usize,
 }
 
 #[derive(Clone, Debug)]
-struct MonkeyState {
+pub struct MonkeyState {
     inspected: usize,
     holding: Vec<u64>,
 }
@@ -94,8 +136,18 @@ struct MonkeyState {
 /// of monkeys in the process.  A monkey looks at all of the items it's holding, increases
 /// their worry score by the operation, get bored with the item and divides the score by reduce,
 /// then tests the item and throws it to another monkey.
-fn round(configs: &Vec<MonkeyConfig>, states: &mut Vec<MonkeyState>, reduce: u64) {
-    let cap = configs.iter().map(|c| c.test).product::<u64>();
+///
+/// Without relief, worry scores grow too big to store.  Every test only cares about the score
+/// modulo its divisor, so they're kept modulo the product of every divisor.  That only works
+/// when nothing divides the scores, though: dividing a score that's been taken modulo anything
+/// gives a different answer than dividing the real score, a round or more later.  With relief,
+/// the real scores are kept, and it's an error if one gets too big to store.
+pub fn round(configs: &[MonkeyConfig], states: &mut [MonkeyState], reduce: u64) -> Result<()> {
+    let cap = match reduce {
+        1 => configs.iter().try_fold(1, |cap: u64, c| cap.checked_mul(c.test))
+            .ok_or_else(|| Error::solve("the product of the tests is too big to store"))?,
+        _ => u64::MAX,
+    };
 
     for i in 0..configs.len() {
         let config = &configs[i];
@@ -105,27 +157,31 @@ fn round(configs: &Vec<MonkeyConfig>, states: &mut Vec<MonkeyState>, reduce: u64
         states[i].holding.clear();
 
         for item in items {
-            let new_item = ((config.operation)(item) / reduce) % cap;
+            let worry = (config.operation)(item)
+                .ok_or_else(|| Error::solve(format!("monkey {} makes worry level {} too big to store", i, item)))?;
+            let new_item = (worry / reduce) % cap;
 
-            if new_item % config.test == 0 {
+            if new_item.is_multiple_of(config.test) {
                 states[config.true_monkey].holding.push(new_item);
             } else {
                 states[config.false_monkey].holding.push(new_item);
             };
         }
     }
+
+    Ok(())
 }
 
 /// monkey_business returns the monkey business score, which is the product of the number of times
 /// the two most active monkeys inspected items over the given number of rounds.
-fn monkey_business(configs: &Vec<MonkeyConfig>, states: &Vec<MonkeyState>, reduce: u64, rounds: usize) -> u64 {
-    let mut states = states.clone();
+pub fn monkey_business(configs: &[MonkeyConfig], states: &[MonkeyState], reduce: u64, rounds: usize) -> Result<u64> {
+    let mut states = states.to_vec();
 
     for _ in 0..rounds {
-        round(configs, &mut states, reduce)
+        round(configs, &mut states, reduce)?
     }
 
-    states.iter().map(|m| m.inspected as u64).sorted().rev().take(2).product()
+    Ok(states.iter().map(|m| m.inspected as u64).sorted().rev().take(2).product())
 }
 
 #[cfg(test)]
@@ -134,9 +190,13 @@ mod tests {
 
     #[test]
     fn test_monkey_business() {
-        let (configs, states) = parse_monkeys("input/day11_sample.txt");
+        let (configs, states) = Day11::parse(include_str!("../input/day11_sample.txt")).unwrap();
+
+        assert_eq!(10605, monkey_business(&configs, &states, 3, 20).unwrap());
+        assert_eq!(2713310158, monkey_business(&configs, &states, 1, 10_000).unwrap());
 
-        assert_eq!(10605, monkey_business(&configs, &states, 3, 20));
-        assert_eq!(2713310158, monkey_business(&configs, &states, 1, 10_000));
+        // with relief, worry levels aren't capped, so they overflow eventually.
+        let err = monkey_business(&configs, &states, 3, 100).unwrap_err();
+        assert!(err.to_string().contains("too big to store"), "{}", err);
     }
 }
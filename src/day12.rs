@@ -1,172 +1,100 @@
-use std::cmp::Ordering;
-use std::collections::BinaryHeap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
 use itertools::Itertools;
-use rayon::prelude::*;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let map = parse_map("input/day12.txt");
+use crate::error::{Error, Result};
+use crate::grid::{Grid, Point};
+use crate::search::{bfs, bfs_reverse};
+use crate::solution::Solution;
 
-    println!("Day 12");
-    println!("Part 1: {}", fewest_steps(&map));
-    println!("Part 2: {}", best_start(&map));
-}
+pub struct Day12;
 
-#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
-struct Position {
-    row: usize,
-    col: usize,
-}
+impl Solution for Day12 {
+    type Input = Map;
+    type Part1 = usize;
+    type Part2 = usize;
 
-impl Position {
-    fn at(row: usize, col: usize) -> Self {
-        Position { row, col }
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_map(input)
     }
-}
-
-struct Map {
-    heights: Vec<Vec<char>>,
-    start: Position,
-    end: Position,
-}
 
-impl Map {
-    fn neighbors(&self, pos: &Position) -> Vec<Position> {
-        let mut neighbors = Vec::new();
-
-        if pos.row > 0 {
-            neighbors.push(Position::at(pos.row - 1, pos.col));
-        }
-
-        if pos.row < self.heights.len() - 1 {
-            neighbors.push(Position::at(pos.row + 1, pos.col));
-        }
-
-        if pos.col > 0 {
-            neighbors.push(Position::at(pos.row, pos.col - 1));
-        }
-
-        if pos.col < self.heights[0].len() - 1 {
-            neighbors.push(Position::at(pos.row, pos.col + 1));
-        }
-
-        let height = self.height(pos);
-
-        neighbors.into_iter()
-            .filter(|neighbor| self.height(neighbor) <= height + 1)
-            .collect_vec()
+    fn part1(map: &Self::Input) -> Result<Self::Part1> {
+        fewest_steps(map).ok_or_else(|| Error::solve("no path from S to E"))
     }
 
-    fn height(&self, pos: &Position) -> i32 {
-        return self.heights[pos.row][pos.col] as i32 - 'a' as i32;
+    fn part2(map: &Self::Input) -> Result<Self::Part2> {
+        best_start(map).ok_or_else(|| Error::solve("no path from any 'a' to E"))
     }
 }
 
-fn parse_map(filename: &str) -> Map {
-    let f = File::open(filename);
-    let f = BufReader::new(f.unwrap());
-
-    let mut heights = Vec::new();
-    let mut start = Position::at(0, 0);
-    let mut end = Position::at(0, 0);
-
-    for (row, line) in f.lines().flatten().enumerate() {
-        let mut map_row = Vec::new();
-
-        for (col, c) in line.chars().enumerate() {
-            if c == 'S' {
-                start = Position::at(row, col);
-                map_row.push('a');
-            } else if c == 'E' {
-                end = Position::at(row, col);
-                map_row.push('z');
-            } else {
-                map_row.push(c);
-            }
-        }
-
-        heights.push(map_row);
-    }
-
-    Map { heights, start, end }
+pub struct Map {
+    heights: Grid<char>,
+    start: Point,
+    end: Point,
 }
 
-/// fewest_steps returns the fewest number of steps it takes to get from start to end on the map,
-/// only going up at most one letter.
-fn fewest_steps(map: &Map) -> usize {
-    #[derive(Copy, Clone, Eq, PartialEq)]
-    struct State {
-        cost: usize,
-        position: Position,
+impl Map {
+    /// neighbors returns the positions that can be stepped to from pos.
+    fn neighbors(&self, pos: Point) -> Vec<Point> {
+        self.heights.neighbors(pos)
+            .filter(|&neighbor| self.climbable(pos, neighbor))
+            .collect_vec()
     }
 
-    impl Ord for State {
-        fn cmp(&self, other: &Self) -> Ordering {
-            other.cost.cmp(&self.cost)
-                .then_with(|| self.position.row.cmp(&other.position.row))
-                .then_with(|| self.position.col.cmp(&other.position.col))
-        }
+    /// predecessors returns the positions that can step to pos.
+    fn predecessors(&self, pos: Point) -> Vec<Point> {
+        self.heights.neighbors(pos)
+            .filter(|&neighbor| self.climbable(neighbor, pos))
+            .collect_vec()
     }
 
-    impl PartialOrd for State {
-        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-            Some(self.cmp(other))
+    /// climbable returns whether it's possible to step from one position to another, which can
+    /// be at most one higher.
+    fn climbable(&self, from: Point, to: Point) -> bool {
+        match (self.height(from), self.height(to)) {
+            (Some(from), Some(to)) => to <= from + 1,
+            _ => false,
         }
     }
 
-    // dist[row][col] = current shortest distance from `start` to `node`
-    let mut dist: Vec<Vec<usize>> = vec![vec![usize::MAX; map.heights[0].len()]; map.heights.len()];
-
-    let mut heap = BinaryHeap::new();
-
-    dist[map.start.row][map.start.col] = 0;
-    heap.push(State { cost: 0, position: map.start });
-
-    while let Some(State { cost, position }) = heap.pop() {
-        // Found the goal - this is the shortest path
-        if position == map.end {
-            return cost;
-        }
-
-        // Already found a shorter path to this position.
-        if cost > dist[position.row][position.col] {
-            continue;
-        }
-
-        // For each node we can reach, see if we can find a way with a lower cost through this pos.
-        for neighbor in map.neighbors(&position) {
-            let next = State { cost: cost + 1, position: neighbor };
+    /// height returns the height of the given position, counting up from 0 at 'a', or None if
+    /// it's off the map.
+    fn height(&self, pos: Point) -> Option<i32> {
+        self.heights.get(pos).map(|&height| height as i32 - 'a' as i32)
+    }
+}
 
-            if next.cost < dist[neighbor.row][neighbor.col] {
-                heap.push(next);
-                dist[neighbor.row][neighbor.col] = next.cost;
+/// parse_map parses a heightmap, where heights are a-z.  The start is marked with 'S' at height 'a',
+/// and the end is marked with 'E' at height 'z'.
+pub fn parse_map(input: &str) -> Result<Map> {
+    let mut heights = Grid::parse(input, |c| match c {
+        'a'..='z' | 'S' | 'E' => Ok(c),
+        _ => Err(Error::parse(&c.to_string(), "heights must be a-z")),
+    })?;
+
+    match (heights.find(|&c| c == 'S'), heights.find(|&c| c == 'E')) {
+        (Some(start), Some(end)) => {
+            for (pos, height) in [(start, 'a'), (end, 'z')] {
+                if let Some(cell) = heights.get_mut(pos) {
+                    *cell = height;
+                }
             }
-        }
+
+            Ok(Map { heights, start, end })
+        },
+        _ => Err(Error::parse("", "map must have a start 'S' and end 'E'")),
     }
+}
 
-    usize::MAX
+/// fewest_steps returns the fewest number of steps it takes to get from start to end on the map,
+/// only going up at most one letter, or None if the end can't be reached.
+pub fn fewest_steps(map: &Map) -> Option<usize> {
+    bfs(map.start, |&pos| map.neighbors(pos), |&pos| pos == map.end).map(|(steps, _)| steps)
 }
 
-/// best_start returns the shortest number of steps it takes to get from an 'a' position to the end.
-fn best_start(map: &Map) -> usize {
-    let starts = (0..map.heights.len())
-        .cartesian_product(0..map.heights[0].len())
-        .map(|(row, col)| Position::at(row, col))
-        .filter(|pos| map.height(pos) == 0)
-        .collect_vec();
-    
-    starts.into_par_iter().map(|start| {
-        let new_map = Map {
-            heights: map.heights.clone(),
-            start,
-            end: map.end,
-        };
-
-        fewest_steps(&new_map)
-    }).min().unwrap_or(usize::MAX)
+/// best_start returns the shortest number of steps it takes to get from an 'a' position to the end,
+/// or None if the end can't be reached from any of them.  Searching backward from the end finds
+/// the closest 'a' in a single search.
+pub fn best_start(map: &Map) -> Option<usize> {
+    bfs_reverse(map.end, |&pos| map.predecessors(pos), |&pos| map.height(pos) == Some(0)).map(|(steps, _)| steps)
 }
 
 #[cfg(test)]
@@ -175,15 +103,15 @@ mod tests {
 
     #[test]
     fn test_fewest_steps() {
-        let map = parse_map("input/day12_sample.txt");
+        let map = Day12::parse(include_str!("../input/day12_sample.txt")).unwrap();
 
-        assert_eq!(31, fewest_steps(&map));
+        assert_eq!(Some(31), fewest_steps(&map));
     }
 
     #[test]
     fn test_best_start() {
-        let map = parse_map("input/day12_sample.txt");
+        let map = Day12::parse(include_str!("../input/day12_sample.txt")).unwrap();
 
-        assert_eq!(29, best_start(&map));
+        assert_eq!(Some(29), best_start(&map));
     }
 }
\ No newline at end of file
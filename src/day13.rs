@@ -1,44 +1,58 @@
 use std::cmp::Ordering;
 use std::fmt::{Debug, Formatter};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use std::str::FromStr;
-use std::string::ParseError;
-use itertools::Itertools;
 use serde_json::Value;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let pairs = parse_pairs("input/day13.txt");
-    let nodes = parse_nodes("input/day13.txt");
+use crate::error::{Error, Result};
+use crate::solution::Solution;
 
-    println!("Day 13");
-    println!("Part 1: {}", count_ordered(&pairs));
-    println!("Part 2: {}", divider(&nodes));
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = (Vec<Pair>, Vec<Node>);
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok((parse_pairs(input)?, parse_nodes(input)?))
+    }
+
+    fn part1((pairs, _): &Self::Input) -> Result<Self::Part1> {
+        Ok(count_ordered(pairs))
+    }
+
+    fn part2((_, nodes): &Self::Input) -> Result<Self::Part2> {
+        Ok(divider(nodes))
+    }
 }
 
 #[derive(Eq, PartialEq, Clone)]
-enum Node {
+pub enum Node {
     Array(Vec<Node>),
     Number(i64),
 }
 
 impl FromStr for Node {
-    type Err = ParseError;
+    type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        fn parse(value: Value) -> Node {
+    fn from_str(s: &str) -> Result<Self> {
+        fn parse(s: &str, value: Value) -> Result<Node> {
             match value {
-                Value::Array(arr) => Node::Array(arr.into_iter().map(parse).collect_vec()),
-                Value::Number(num) => Node::Number(num.as_i64().unwrap()),
-                _ => panic!("Unsupported value: {:?}", value),
+                Value::Array(arr) => {
+                    let nodes = arr.into_iter().map(|value| parse(s, value)).collect::<Result<Vec<Node>>>()?;
+                    Ok(Node::Array(nodes))
+                },
+                Value::Number(num) => {
+                    num.as_i64().map(Node::Number).ok_or_else(|| Error::parse(s, "packet values must be integers"))
+                },
+                _ => Err(Error::parse(s, "packets may only contain lists and integers")),
             }
         }
 
         // [[1],[2,3,4]]
-        let json = serde_json::from_str(s).unwrap();
+        let json = serde_json::from_str(s).map_err(|_| Error::parse(s, "invalid packet"))?;
 
-        Ok(parse(json))
+        parse(s, json)
     }
 }
 
@@ -46,23 +60,14 @@ impl Debug for Node {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Node::Array(values) => {
-                let result = write!(f, "[");
-                if result.is_err() {
-                    return result;
-                }
+                write!(f, "[")?;
 
                 for (i, value) in values.iter().enumerate() {
                     if i > 0 {
-                        let result = write!(f, ",");
-                        if result.is_err() {
-                            return result;
-                        }
+                        write!(f, ",")?;
                     }
 
-                    let result = value.fmt(f);
-                    if result.is_err() {
-                        return result;
-                    }
+                    value.fmt(f)?;
                 }
 
                 write!(f, "]")
@@ -77,10 +82,8 @@ impl Ord for Node {
         fn is_less(left: &Node, right: &Node) -> Option<bool> {
             match (left, right) {
                 (Node::Array(l), Node::Array(r)) => {
-                    l.into_iter().zip(r.into_iter())
-                        .map(|(left_item, right_item)| is_less(left_item, right_item))
-                        .flatten()
-                        .nth(0)
+                    l.iter().zip(r)
+                        .find_map(|(left_item, right_item)| is_less(left_item, right_item))
                         .or(if l.len() == r.len() {
                             None
                         } else {
@@ -117,41 +120,47 @@ impl PartialOrd for Node {
     }
 }
 
-struct Pair {
-    left: Node,
-    right: Node,
+pub struct Pair {
+    pub left: Node,
+    pub right: Node,
 }
 
-fn parse_pairs(filename: &str) -> Vec<Pair> {
-    let f = File::open(filename);
-    let f = BufReader::new(f.unwrap());
-
-    let mut lines = f.lines().flatten();
+/// parse_pairs parses pairs of packets, which are two lines followed by a blank line.
+pub fn parse_pairs(input: &str) -> Result<Vec<Pair>> {
+    let mut lines = input.lines().enumerate();
     let mut pairs = Vec::new();
 
+    // parse_node parses a single packet, recording the line number if it isn't valid.
+    fn parse_node((i, line): (usize, &str)) -> Result<Node> {
+        line.parse().map_err(|err: Error| err.at_line(i + 1))
+    }
+
     // Pairs are two lines followed by a newline.
     loop {
         match (lines.next(), lines.next(), lines.next()) {
             (Some(left), Some(right), _) => pairs.push(Pair {
-                left: left.parse().unwrap(),
-                right: right.parse().unwrap(),
+                left: parse_node(left)?,
+                right: parse_node(right)?,
             }),
-            _ => return pairs,
+            (Some((i, left)), None, _) => {
+                return Err(Error::parse(left, "packet is missing its pair").at_line(i + 1))
+            },
+            _ => return Ok(pairs),
         }
     }
 }
 
-fn parse_nodes(filename: &str) -> Vec<Node> {
-    let f = File::open(filename);
-    let f = BufReader::new(f.unwrap());
-
-    f.lines().flatten()
-        .filter(|line| !line.is_empty())
-        .map(|line| line.parse().unwrap())
-        .collect_vec()
+/// parse_nodes parses every packet in the input, ignoring the blank lines between pairs.
+pub fn parse_nodes(input: &str) -> Result<Vec<Node>> {
+    input.lines().enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| line.parse().map_err(|err: Error| err.at_line(i + 1)))
+        .collect()
 }
 
-fn count_ordered(pairs: &Vec<Pair>) -> usize {
+/// count_ordered returns the sum of the indexes, counting from 1, of the pairs that are
+/// in the right order.
+pub fn count_ordered(pairs: &[Pair]) -> usize {
     pairs.iter().enumerate()
         .filter(|(_, pair)| pair.left < pair.right)
         .map(|(i, _)| i + 1)
@@ -159,15 +168,14 @@ fn count_ordered(pairs: &Vec<Pair>) -> usize {
 }
 
 /// divider sorts all of the nodes, and inserts packets `[[2]]` and `[[6]]`.  It returns
-/// the product of the indexes of the divider packets.
-fn divider(nodes: &Vec<Node>) -> usize {
-    let sorted = nodes.iter().cloned().sorted().collect_vec();
-
+/// the product of the indexes of the divider packets.  A divider's index only depends on how
+/// many nodes sort before it, so they're counted instead of sorted.
+pub fn divider(nodes: &[Node]) -> usize {
     let two = "[[2]]".parse::<Node>().unwrap();
-    let two_index = sorted.iter().position(|node| two < *node).unwrap() + 1;
+    let two_index = nodes.iter().filter(|&node| *node <= two).count() + 1;
 
     let six = "[[6]]".parse::<Node>().unwrap();
-    let six_index = sorted.iter().position(|node| six < *node).unwrap() + 2;
+    let six_index = nodes.iter().filter(|&node| *node <= six).count() + 2;
 
     two_index * six_index
 }
@@ -178,17 +186,17 @@ mod tests {
 
     #[test]
     fn test_count_ordered() {
-        let pairs = parse_pairs("input/day13_sample.txt");
+        let (pairs, _) = Day13::parse(include_str!("../input/day13_sample.txt")).unwrap();
 
         assert_eq!(13, count_ordered(&pairs));
     }
 
     #[test]
     fn test_ordered_sample() {
-        let pairs = parse_pairs("input/day13_sample.txt");
+        let (pairs, _) = Day13::parse(include_str!("../input/day13_sample.txt")).unwrap();
         let expected = vec![true, true, false, true, false, true, false, false];
 
-        for (i, (pair, exp)) in pairs.into_iter().zip(expected.into_iter()).enumerate() {
+        for (i, (pair, exp)) in pairs.into_iter().zip(expected).enumerate() {
             assert_eq!(exp, pair.left < pair.right, "Pair {}", i+1);
         }
     }
@@ -215,19 +223,19 @@ mod tests {
             right: "[[1,5,[9,5]]]".parse().unwrap(),
         };
 
-        assert_eq!(false, pair.left < pair.right);
+        assert!(pair.left >= pair.right);
 
         let pair = Pair {
             left: "[[]]".parse().unwrap(),
             right: "[[2]]".parse().unwrap(),
         };
 
-        assert_eq!(true, pair.left < pair.right);
+        assert!(pair.left < pair.right);
     }
 
     #[test]
     fn test_divider() {
-        let nodes = parse_nodes("input/day13_sample.txt");
+        let (_, nodes) = Day13::parse(include_str!("../input/day13_sample.txt")).unwrap();
 
         assert_eq!(140, divider(&nodes));
     }
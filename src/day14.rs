@@ -1,153 +1,132 @@
-use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use itertools::Itertools;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let lines = parse_lines("input/day14.txt");
+use crate::error::{Error, parse_num, Result};
+use crate::grid::{Point, SparseGrid};
+use crate::solution::Solution;
 
-    println!("Day 14");
-    println!("Part 1: {}", sand_falling(&lines));
-    println!("Part 2: {}", sand_floor(&lines));
-}
+pub struct Day14;
 
-fn parse_lines(filename: &str) -> Vec<Vec<Point>> {
-    let f = File::open(filename);
-    let f = BufReader::new(f.unwrap());
+impl Solution for Day14 {
+    type Input = Vec<Vec<Point>>;
+    type Part1 = usize;
+    type Part2 = usize;
 
-    f.lines().flatten().map(|line| line.split(" -> ").map(|point| {
-        let mut coords = point.split(",");
-        Point {
-            x: coords.next().unwrap().parse().unwrap(),
-            y: coords.next().unwrap().parse().unwrap(),
-        }
-    }).collect_vec()).collect_vec()
-}
-
-#[derive(Eq, PartialEq, Hash, Copy, Clone)]
-struct Point {
-    x: i32,
-    y: i32,
-}
-
-impl Point {
-    fn at(x: i32, y: i32) -> Self {
-        Point { x, y }
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_lines(input)
     }
 
-    fn to(&self, end: &Self) -> Vec<Self> {
-        if self.x == end.x {
-            (self.y.min(end.y)..=self.y.max(end.y)).map(|y| Point::at(self.x, y)).collect_vec()
-        } else {
-            (self.x.min(end.x)..=self.x.max(end.x)).map(|x| Point::at(x, self.y)).collect_vec()
-        }
+    fn part1(lines: &Self::Input) -> Result<Self::Part1> {
+        Ok(sand_falling(lines))
     }
 
-    fn down(&self) -> Self {
-        return Self::at(self.x, self.y + 1);
+    fn part2(lines: &Self::Input) -> Result<Self::Part2> {
+        Ok(sand_floor(lines))
     }
+}
 
-    fn left(&self) -> Self {
-        return Self::at(self.x - 1, self.y + 1);
-    }
+/// parse_lines parses rock paths, which look like '498,4 -> 498,6 -> 496,6'.
+/// Each segment of a path is a horizontal or vertical line.
+pub fn parse_lines(input: &str) -> Result<Vec<Vec<Point>>> {
+    fn parse_point(point: &str) -> Result<Point> {
+        let (x, y) = point.split_once(',').ok_or_else(|| Error::parse(point, "invalid point"))?;
 
-    fn right(&self) -> Self {
-        return Self::at(self.x + 1, self.y + 1);
+        Ok(Point::at(parse_num(y)?, parse_num(x)?))
     }
-}
 
-/// sand_falling returns the number of grains of sand that come to rest after being dropped
-/// from 500,0 with the given rocks, with no floor.
-fn sand_falling(lines: &Vec<Vec<Point>>) -> usize {
-    let mut filled = draw_rocks(lines);
+    let paths = input.lines().enumerate().map(|(i, line)| {
+        let path = line.split(" -> ")
+            .map(parse_point)
+            .collect::<Result<Vec<Point>>>()
+            .map_err(|err| err.at_line(i + 1))?;
 
-    let num_rocks = filled.len();
-    let lowest_rock = filled.iter().map(|rock| rock.y).max().unwrap();
-
-    // Sand falls from 500,0, and tries to drop down,
-    // down and to the left, then down and to the right.
-    loop {
-        let mut sand = Point::at(500, 0);
-        if filled.contains(&sand) {
-            break
+        if path.windows(2).any(|segment| segment[0].row != segment[1].row && segment[0].col != segment[1].col) {
+            return Err(Error::parse(line, "rock paths must be horizontal or vertical").at_line(i + 1));
         }
 
-        while sand.y < lowest_rock {
-            let (down, left, right) = (sand.down(), sand.left(), sand.right());
-
-            if !filled.contains(&down) {
-                sand = down;
-            } else if !filled.contains(&left) {
-                sand = left;
-            } else if !filled.contains(&right) {
-                sand = right;
-            } else {
-                filled.insert(sand);
-                break;
-            }
-        }
+        Ok(path)
+    }).collect::<Result<Vec<Vec<Point>>>>()?;
 
-        if sand.y >= lowest_rock {
-            break;
-        }
+    if paths.is_empty() {
+        return Err(Error::parse("", "no rock paths"));
     }
 
-    filled.len() - num_rocks
+    Ok(paths)
+}
+
+/// Tile is something that fills a point in the cave.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+enum Tile {
+    Rock, Sand,
+}
+
+/// segment returns the points on the straight line between two points, including both ends.
+fn segment(start: Point, end: Point) -> Vec<Point> {
+    if start.col == end.col {
+        (start.row.min(end.row)..=start.row.max(end.row)).map(|row| Point::at(row, start.col)).collect_vec()
+    } else {
+        (start.col.min(end.col)..=start.col.max(end.col)).map(|col| Point::at(start.row, col)).collect_vec()
+    }
+}
+
+/// sand_falling returns the number of grains of sand that come to rest after being dropped
+/// from 500,0 with the given rocks, with no floor.
+pub fn sand_falling(lines: &[Vec<Point>]) -> usize {
+    pour_sand(lines, false)
 }
 
 /// sand_floor returns the number of grains of sand that come to rest after being dropped
 /// from 500,0 with the given rocks, with a floor below the lowest rock.
-fn sand_floor(lines: &Vec<Vec<Point>>) -> usize {
-    let mut filled = draw_rocks(lines);
+pub fn sand_floor(lines: &[Vec<Point>]) -> usize {
+    pour_sand(lines, true)
+}
+
+/// pour_sand drops grains of sand from 500,0 until they stop coming to rest, and returns the
+/// number of grains that came to rest.  With a floor two rows below the lowest rock, sand
+/// stops when it fills the source.  Without a floor, it stops when sand falls past the lowest rock.
+fn pour_sand(lines: &[Vec<Point>], floor: bool) -> usize {
+    let mut cave = draw_rocks(lines);
 
-    let num_rocks = filled.len();
-    let lowest_rock = filled.iter().map(|rock| rock.y).max().unwrap();
+    let source = Point::at(0, 500);
+    let lowest_rock = cave.bounds().map(|(_, bottom_right)| bottom_right.row).unwrap_or(0);
 
     // Sand falls from 500,0, and tries to drop down,
     // down and to the left, then down and to the right.
-    loop {
-        let mut sand = Point::at(500, 0);
-        if filled.contains(&sand) {
-            break
-        }
+    let fall = [Point::DOWN, Point::DOWN + Point::LEFT, Point::DOWN + Point::RIGHT];
 
-        while sand.y < lowest_rock + 1 {
-            let (down, left, right) = (sand.down(), sand.left(), sand.right());
-
-            if !filled.contains(&down) {
-                sand = down;
-            } else if !filled.contains(&left) {
-                sand = left;
-            } else if !filled.contains(&right) {
-                sand = right;
-            } else {
-                filled.insert(sand);
-                break;
+    while !cave.contains(source) {
+        let mut sand = source;
+
+        while let Some(next) = fall.iter().map(|&dir| sand + dir).find(|&next| !cave.contains(next)) {
+            if sand.row > lowest_rock {
+                break
             }
+
+            sand = next;
         }
 
-        if sand.y == lowest_rock + 1 {
-            filled.insert(sand);
+        if sand.row > lowest_rock && !floor {
+            break
         }
+
+        cave.insert(sand, Tile::Sand);
     }
 
-    filled.len() - num_rocks
+    cave.iter().filter(|(_, &tile)| tile == Tile::Sand).count()
 }
 
-fn draw_rocks(lines: &Vec<Vec<Point>>) -> HashSet<Point> {
-    let mut filled: HashSet<Point> = HashSet::new();
+fn draw_rocks(lines: &[Vec<Point>]) -> SparseGrid<Tile> {
+    let mut cave = SparseGrid::new();
 
     // Fill in all of the solid lines.
     for line in lines {
-        for segment in line.windows(2) {
-            for point in segment[0].to(&segment[1]) {
-                filled.insert(point);
+        for ends in line.windows(2) {
+            for point in segment(ends[0], ends[1]) {
+                cave.insert(point, Tile::Rock);
             }
         }
     }
 
-    filled
+    cave
 }
 
 #[cfg(test)]
@@ -156,13 +135,13 @@ mod tests {
 
     #[test]
     fn test_sand_falling() {
-        let lines = parse_lines("input/day14_sample.txt");
+        let lines = Day14::parse(include_str!("../input/day14_sample.txt")).unwrap();
         assert_eq!(24, sand_falling(&lines));
     }
 
     #[test]
     fn test_sand_floor() {
-        let lines = parse_lines("input/day14_sample.txt");
+        let lines = Day14::parse(include_str!("../input/day14_sample.txt")).unwrap();
         assert_eq!(93, sand_floor(&lines));
     }
 }
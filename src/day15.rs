@@ -1,122 +1,104 @@
-use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use std::ops::RangeInclusive;
 use std::str::FromStr;
-use std::string::ParseError;
 
-use itertools::Itertools;
 use regex::Regex;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let sensors = load_sensors("input/day15.txt");
+use crate::error::{Error, parse_lines, parse_num, Result};
+use crate::grid::Point;
+use crate::intervals::IntervalSet;
+use crate::solution::Solution;
 
-    println!("Day 15");
-    println!("Part 1: {}", no_beacon(&sensors, 2000000));
-    println!("Part 2: {}", tuning_frequency(&sensors, 4000000));
-}
+pub struct Day15;
 
-fn load_sensors(filename: &str) -> Vec<Sensor> {
-    let f = File::open(filename);
-    let f = BufReader::new(f.unwrap());
+impl Solution for Day15 {
+    type Input = Vec<Sensor>;
+    type Part1 = u128;
+    type Part2 = i64;
 
-    f.lines().flatten().map(|line| line.parse()).flatten().collect_vec()
-}
+    fn parse(input: &str) -> Result<Self::Input> {
+        load_sensors(input)
+    }
 
-#[derive(Eq, PartialEq, Hash, Copy, Clone)]
-struct Point {
-    col: i64,
-    row: i64,
-}
+    fn part1(sensors: &Self::Input) -> Result<Self::Part1> {
+        Ok(no_beacon(sensors, 2000000))
+    }
 
-impl Point {
-    fn at(col: i64, row: i64) -> Self {
-        Point { col, row }
+    fn part2(sensors: &Self::Input) -> Result<Self::Part2> {
+        tuning_frequency(sensors, 4000000)
     }
 }
 
-struct Sensor {
+/// load_sensors parses the sensors and the closest beacon to each, one per line.
+pub fn load_sensors(input: &str) -> Result<Vec<Sensor>> {
+    parse_lines(input)
+}
+
+pub struct Sensor {
     sensor: Point,
     beacon: Point,
     dist: i64
 }
 
 impl FromStr for Sensor {
-    type Err = ParseError;
+    type Err = Error;
 
-    fn from_str(str: &str) -> Result<Self, Self::Err> {
+    fn from_str(str: &str) -> Result<Self> {
         // Sensor at x=2, y=18: closest beacon is at x=-2, y=15
-        let re = Regex::new(r"Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)").unwrap();
-        let captures = re.captures(str).unwrap();
+        let re = Regex::new(r"^Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)$").unwrap();
+        let captures = re.captures(str).ok_or_else(|| Error::parse(str, "invalid sensor"))?;
 
-        let sensor =Point::at(captures[1].parse().unwrap(), captures[2].parse().unwrap());
-        let beacon = Point::at(captures[3].parse().unwrap(), captures[4].parse().unwrap());
-        let dist = (sensor.col - beacon.col).abs() + (sensor.row - beacon.row).abs();
+        let sensor = Point::at(parse_num(&captures[2])?, parse_num(&captures[1])?);
+        let beacon = Point::at(parse_num(&captures[4])?, parse_num(&captures[3])?);
+        let dist = sensor.manhattan(beacon);
 
         Ok(Sensor { sensor, beacon, dist })
     }
 }
 
-/// no_beacon returns the number of squares on the given row that can't have a beacon.
-fn no_beacon(sensors: &Vec<Sensor>, row: i64) -> i64 {
-    let mut ranges: Vec<RangeInclusive<i64>> = Vec::new();
-    let mut beacons: HashSet<Point> = HashSet::new();
-
-    for sensor in sensors {
-        if (sensor.sensor.row - row).abs() > sensor.dist {
-            continue
-        }
-
+impl Sensor {
+    /// coverage returns the squares on the given row that are within range of this sensor,
+    /// or None if the row is too far away.
+    pub fn coverage(&self, row: i64) -> Option<RangeInclusive<i64>> {
         // number of squares to the left and right of sensor.x on the line
-        let num_squares = sensor.dist - (row - sensor.sensor.row).abs();
-
-        ranges.push(sensor.sensor.col - num_squares..=sensor.sensor.col + num_squares);
-
-        if sensor.beacon.row == row {
-            beacons.insert(sensor.beacon);
+        let num_squares = self.dist - (row - self.sensor.row).abs();
+        if num_squares < 0 {
+            return None
         }
-    }
 
-    // Collapse overlapping ranges
-    ranges.sort_by_key(|range| *range.start());
+        Some(self.sensor.col - num_squares..=self.sensor.col + num_squares)
+    }
+}
 
-    let mut i = 0;
-    while i < ranges.len() - 1 {
-        if ranges[i].end() >= ranges[i+1].start() {
-            let end = *ranges[i].end().max(ranges[i+1].end());
+/// covered returns the squares on the given row that are within range of any sensor.
+pub fn covered(sensors: &[Sensor], row: i64) -> IntervalSet {
+    sensors.iter().filter_map(|sensor| sensor.coverage(row)).collect()
+}
 
-            ranges[i] = *ranges[i].start()..=end;
-            ranges.remove(i+1);
-        } else {
-            i += 1;
-        }
-    }
+/// no_beacon returns the number of squares on the given row that can't have a beacon.
+pub fn no_beacon(sensors: &[Sensor], row: i64) -> u128 {
+    let mut squares = covered(sensors, row);
 
-    // Remove beacons and sensors
-    for beacon in beacons {
-        let mut i = 0;
-        while i < ranges.len() {
-            if ranges[i].contains(&beacon.col) {
-                let first = *ranges[i].start()..=beacon.col-1;
-                let second = beacon.col+1..=*ranges[i].end();
-
-                ranges[i] = first;
-                ranges.insert(i+1, second);
-                i += 2;
-            } else {
-                i += 1;
-            }
-        }
+    // Remove beacons
+    for beacon in sensors.iter().map(|sensor| sensor.beacon).filter(|beacon| beacon.row == row) {
+        squares.remove(beacon.col..=beacon.col);
     }
 
-    ranges.iter().map(|range| range.end() - range.start() + 1).sum()
+    squares.len()
 }
 
 /// tuning_frequency returns the tuning frequency of the distress beacon found between 0..=bound,
-/// where a frequency is x * 4000000 + y.
-fn tuning_frequency(sensors: &Vec<Sensor>, bound: i64) -> i64 {
-    todo!()
+/// where a frequency is x * 4000000 + y.  The beacon is the only square in the search area that
+/// isn't covered by a sensor, so each row is searched for a square that isn't covered.
+pub fn tuning_frequency(sensors: &[Sensor], bound: i64) -> Result<i64> {
+    let area = IntervalSet::from(0..=bound);
+
+    for row in 0..=bound {
+        if let Some(gap) = area.difference(&covered(sensors, row)).iter().next() {
+            return Ok(gap.start() * 4000000 + row);
+        }
+    }
+
+    Err(Error::solve(format!("no distress beacon within 0..={}", bound)))
 }
 
 #[cfg(test)]
@@ -125,13 +107,13 @@ mod tests {
 
     #[test]
     fn test_no_beacon() {
-        let sensors = load_sensors("input/day15_sample.txt");
+        let sensors = Day15::parse(include_str!("../input/day15_sample.txt")).unwrap();
         assert_eq!(26, no_beacon(&sensors, 10));
     }
 
     #[test]
     fn test_tuning_frequency() {
-        let sensors = load_sensors("input/day15_sample.txt");
-        assert_eq!(56000011, tuning_frequency(&sensors, 20));
+        let sensors = Day15::parse(include_str!("../input/day15_sample.txt")).unwrap();
+        assert_eq!(56000011, tuning_frequency(&sensors, 20).unwrap());
     }
 }
\ No newline at end of file
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use itertools::Itertools;
+use regex::Regex;
+
+use crate::error::{Error, parse_lines, parse_num, Result};
+use crate::solution::Solution;
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Network;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        load_network(input)
+    }
+
+    fn part1(network: &Self::Input) -> Result<Self::Part1> {
+        Ok(most_pressure(network, 30))
+    }
+
+    fn part2(network: &Self::Input) -> Result<Self::Part2> {
+        Ok(most_pressure_with_elephant(network, 26))
+    }
+}
+
+pub struct Valve {
+    name: String,
+    rate: u32,
+    tunnels: Vec<String>,
+}
+
+impl FromStr for Valve {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        // Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+        // Valve HH has flow rate=22; tunnel leads to valve GG
+        let re = Regex::new(r"^Valve (\w+) has flow rate=(\d+); tunnels? leads? to valves? (\w+(?:, \w+)*)$").unwrap();
+        let captures = re.captures(s).ok_or_else(|| Error::parse(s, "invalid valve"))?;
+
+        Ok(Valve {
+            name: captures[1].to_string(),
+            rate: parse_num(&captures[2])?,
+            tunnels: captures[3].split(", ").map(String::from).collect_vec(),
+        })
+    }
+}
+
+/// Network is the valves that have a flow rate, and the number of minutes it takes to walk
+/// between them.  Valves without any flow are only ever walked through, so they're left out.
+pub struct Network {
+    rates: Vec<u32>,
+
+    /// dist[a][b] is the number of minutes it takes to walk from valve a to valve b.
+    dist: Vec<Vec<u32>>,
+
+    /// start[a] is the number of minutes it takes to walk from AA to valve a.
+    start: Vec<u32>,
+}
+
+/// load_network parses the valves and compresses them into a network of the valves with flow.
+/// Every tunnel must lead to a valve that exists, and there must be a valve AA to start at.
+pub fn load_network(input: &str) -> Result<Network> {
+    let valves: Vec<Valve> = parse_lines(input)?;
+    let index: HashMap<&str, usize> = valves.iter().enumerate()
+        .map(|(i, valve)| (valve.name.as_str(), i))
+        .collect();
+
+    // all-pairs shortest paths between every valve (Floyd-Warshall).
+    let mut dist = vec![vec![u32::MAX; valves.len()]; valves.len()];
+
+    for (i, valve) in valves.iter().enumerate() {
+        dist[i][i] = 0;
+
+        for tunnel in &valve.tunnels {
+            let Some(&j) = index.get(tunnel.as_str()) else {
+                return Err(Error::parse(tunnel, "no such valve").at_line(i + 1));
+            };
+            dist[i][j] = 1;
+        }
+    }
+
+    for k in 0..valves.len() {
+        for i in 0..valves.len() {
+            for j in 0..valves.len() {
+                let through = dist[i][k].saturating_add(dist[k][j]);
+                if through < dist[i][j] {
+                    dist[i][j] = through;
+                }
+            }
+        }
+    }
+
+    let Some(&aa) = index.get("AA") else {
+        return Err(Error::parse("", "missing valve AA"));
+    };
+
+    let flowing = (0..valves.len()).filter(|&i| valves[i].rate > 0).collect_vec();
+    if flowing.len() > 64 {
+        return Err(Error::parse("", "more than 64 valves with flow"));
+    }
+
+    Ok(Network {
+        rates: flowing.iter().map(|&i| valves[i].rate).collect(),
+        dist: flowing.iter().map(|&i| flowing.iter().map(|&j| dist[i][j]).collect()).collect(),
+        start: flowing.iter().map(|&i| dist[aa][i]).collect(),
+    })
+}
+
+/// best_pressures returns the most pressure that can be released in the given number of minutes
+/// for each set of valves that can be opened in that time, starting at AA.  Sets of opened valves
+/// are bitmasks of the valves in the network.
+fn best_pressures(network: &Network, minutes: u32) -> HashMap<u64, u32> {
+    fn visit(network: &Network, valve: usize, minutes: u32, opened: u64, pressure: u32, best: &mut HashMap<u64, u32>) {
+        let entry = best.entry(opened).or_insert(0);
+        *entry = pressure.max(*entry);
+
+        for next in 0..network.rates.len() {
+            let dist = network.dist[valve][next];
+
+            // walk to the valve and spend a minute opening it, if there's time left for it to flow.
+            if opened & (1 << next) == 0 && dist < minutes - 1 {
+                let left = minutes - dist - 1;
+                visit(network, next, left, opened | (1 << next), pressure + network.rates[next] * left, best);
+            }
+        }
+    }
+
+    let mut best = HashMap::from([(0, 0)]);
+
+    for first in 0..network.rates.len() {
+        let dist = network.start[first];
+
+        if dist < minutes.saturating_sub(1) {
+            let left = minutes - dist - 1;
+            visit(network, first, left, 1 << first, network.rates[first] * left, &mut best);
+        }
+    }
+
+    best
+}
+
+/// most_pressure returns the most pressure that can be released by opening valves in the given
+/// number of minutes.
+pub fn most_pressure(network: &Network, minutes: u32) -> u32 {
+    best_pressures(network, minutes).into_values().max().unwrap_or(0)
+}
+
+/// most_pressure_with_elephant returns the most pressure that can be released in the given number
+/// of minutes when an elephant opens valves too.  You and the elephant each open a different set
+/// of valves, so the answer is the best pair of sets that don't share any valves.
+pub fn most_pressure_with_elephant(network: &Network, minutes: u32) -> u32 {
+    let best = best_pressures(network, minutes).into_iter()
+        .sorted_by_key(|(_, pressure)| std::cmp::Reverse(*pressure))
+        .collect_vec();
+
+    let mut most = 0;
+
+    for (i, (yours, your_pressure)) in best.iter().enumerate() {
+        // the rest of the sets release less pressure, so they can't do any better than this.
+        if your_pressure * 2 <= most {
+            break
+        }
+
+        for (elephants, elephant_pressure) in &best[i..] {
+            if your_pressure + elephant_pressure <= most {
+                break
+            }
+
+            if yours & elephants == 0 {
+                most = your_pressure + elephant_pressure;
+            }
+        }
+    }
+
+    most
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_most_pressure() {
+        let network = Day16::parse(include_str!("../input/day16_sample.txt")).unwrap();
+        assert_eq!(1651, most_pressure(&network, 30));
+    }
+
+    #[test]
+    fn test_most_pressure_with_elephant() {
+        let network = Day16::parse(include_str!("../input/day16_sample.txt")).unwrap();
+        assert_eq!(1707, most_pressure_with_elephant(&network, 26));
+    }
+}
@@ -1,20 +1,32 @@
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::ops::Add;
 
-use itertools::Itertools;
+use crate::error::{Error, Result};
+use crate::grid::{Grid, Point};
+use crate::solution::Solution;
 
-pub fn solution() {
-    let directions = load_directions("input/day17.txt");
+pub struct Day17;
 
-    println!("Day 17");
-    println!("Part 1: {}", height_after_rocks(&directions, 2022));
+impl Solution for Day17 {
+    type Input = Vec<Direction>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        load_directions(input)
+    }
+
+    fn part1(directions: &Self::Input) -> Result<Self::Part1> {
+        Ok(height_after_rocks(directions, 2022))
+    }
+
+    fn part2(directions: &Self::Input) -> Result<Self::Part2> {
+        Ok(height_after_rocks(directions, 1000000000000))
+    }
 }
 
 #[derive(Eq, PartialEq, Copy, Clone)]
-enum Direction {
+pub enum Direction {
     Left, Right
 }
 
@@ -27,74 +39,28 @@ impl Debug for Direction {
     }
 }
 
-impl From<char> for Direction {
-    fn from(c: char) -> Self {
-        match c {
-            '<' => Direction::Left,
-            '>' => Direction::Right,
-            _ => unreachable!("Invalid direction: '{}'", c)
-        }
-    }
-}
-
-#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
-struct Position {
-    row: isize,
-    col: isize,
-}
-
-impl Position {
-    fn direction(&self, dir: Direction) -> Self {
-        match dir {
-            Direction::Left => self + Position::at(0, -1),
-            Direction::Right => self + Position::at(0, 1),
-        }
-    }
-}
-
-impl Add for Position {
-    type Output = Position;
+impl TryFrom<char> for Direction {
+    type Error = Error;
 
-    fn add(self, rhs: Self) -> Self::Output {
-        Position {
-            row: self.row + rhs.row,
-            col: self.col + rhs.col,
+    fn try_from(c: char) -> Result<Self> {
+        match c {
+            '<' => Ok(Direction::Left),
+            '>' => Ok(Direction::Right),
+            _ => Err(Error::parse(&c.to_string(), "invalid direction")),
         }
     }
 }
 
-impl Add<&Position> for Position {
-    type Output = Position;
-
-    fn add(self, rhs: &Self) -> Self::Output {
-        self + *rhs
-    }
-}
-
-impl Add<Position> for &Position {
-    type Output = Position;
-
-    fn add(self, rhs: Position) -> Self::Output {
-        *self + rhs
-    }
-}
-
-impl Position {
-    fn at(row: isize, col: isize) -> Self {
-        Position { row, col }
-    }
-}
-
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
-enum Rock {
+pub(crate) enum Rock {
     /// Center:
-    /// ```
+    /// ```text
     /// *###
     /// ```
     Dash,
 
     /// Center:
-    /// ```
+    /// ```text
     /// .#.
     /// #*#
     /// .#.
@@ -102,7 +68,7 @@ enum Rock {
     Plus,
 
     /// Center:
-    /// ```
+    /// ```text
     /// ..#
     /// ..#
     /// ##*
@@ -110,7 +76,7 @@ enum Rock {
     L,
 
     /// Center:
-    /// ```
+    /// ```text
     /// *
     /// #
     /// #
@@ -119,7 +85,7 @@ enum Rock {
     I,
 
     /// Center:
-    /// ```
+    /// ```text
     /// *#
     /// ##
     /// ```
@@ -127,75 +93,79 @@ enum Rock {
 }
 
 impl Rock {
-    fn order() -> Vec<Rock> {
+    pub(crate) fn order() -> Vec<Rock> {
         vec![Rock::Dash, Rock::Plus, Rock::L, Rock::I, Rock::Square]
     }
 
-    fn positions(&self, pos: &Position) -> Vec<Position> {
+    fn positions(&self, pos: Point) -> Vec<Point> {
         match self {
             Rock::Dash => vec![
-                pos + Position::at(0, 0),
-                pos + Position::at(0, 1),
-                pos + Position::at(0, 2),
-                pos + Position::at(0, 3),
+                pos + Point::at(0, 0),
+                pos + Point::at(0, 1),
+                pos + Point::at(0, 2),
+                pos + Point::at(0, 3),
             ],
             Rock::Plus => vec![
-                pos + Position::at(0, -1),
-                pos + Position::at(-1, 0),
-                pos + Position::at(0, 0),
-                pos + Position::at(1, 0),
-                pos + Position::at(0, 1),
+                pos + Point::at(0, -1),
+                pos + Point::at(-1, 0),
+                pos + Point::at(0, 0),
+                pos + Point::at(1, 0),
+                pos + Point::at(0, 1),
             ],
             Rock::L => vec![
-                pos + Position::at(0, -2),
-                pos + Position::at(0, -1),
-                pos + Position::at(0, 0),
-                pos + Position::at(1, 0),
-                pos + Position::at(2, 0),
+                pos + Point::at(0, -2),
+                pos + Point::at(0, -1),
+                pos + Point::at(0, 0),
+                pos + Point::at(1, 0),
+                pos + Point::at(2, 0),
             ],
             Rock::I => vec![
-                pos + Position::at(0, 0),
-                pos + Position::at(-1, 0),
-                pos + Position::at(-2, 0),
-                pos + Position::at(-3, 0),
+                pos + Point::at(0, 0),
+                pos + Point::at(-1, 0),
+                pos + Point::at(-2, 0),
+                pos + Point::at(-3, 0),
             ],
             Rock::Square => vec![
-                pos + Position::at(0, 0),
-                pos + Position::at(0, 1),
-                pos + Position::at(-1, 0),
-                pos + Position::at(-1, 1),
+                pos + Point::at(0, 0),
+                pos + Point::at(0, 1),
+                pos + Point::at(-1, 0),
+                pos + Point::at(-1, 1),
             ],
         }
     }
 }
 
-struct Board {
-    rock_rows: Vec<[bool; 7]>,
+/// Board is the chamber the rocks fall into.  Row 0 is the floor, so rows count upward.
+pub(crate) struct Board {
+    rock_rows: Grid<bool>,
 }
 
 impl Board {
     /// new returns a board with no rocks.
-    fn new() -> Self {
-        Board { rock_rows: Vec::new() }
+    pub(crate) fn new() -> Self {
+        Board { rock_rows: Grid::new(0, 7, false) }
     }
 
     /// start returns the starting position for the given type of rock.  A Rock appears with its
     /// left edge two units away from the left wall and its bottom edge three units above the
     /// highest rock in the room, or the floor.
-    fn start(&self, rock: Rock) -> Position {
+    fn start(&self, rock: Rock) -> Point {
         match rock {
-            Rock::Dash => Position::at(self.len() + 3, 2),
-            Rock::Plus => Position::at(self.len() + 4, 3),
-            Rock::L => Position::at(self.len() + 3, 4),
-            Rock::I => Position::at(self.len() + 6, 2),
-            Rock::Square => Position::at(self.len() + 4, 2),
+            Rock::Dash => Point::at(self.len() + 3, 2),
+            Rock::Plus => Point::at(self.len() + 4, 3),
+            Rock::L => Point::at(self.len() + 3, 4),
+            Rock::I => Point::at(self.len() + 6, 2),
+            Rock::Square => Point::at(self.len() + 4, 2),
         }
     }
 
     /// push returns the position of the rock after being pushed by the wind in the given direction.
     /// If the rock runs into the walls or another rock, it doesn't move.
-    fn push(&self, rock: Rock, old_pos: Position, dir: Direction) -> Position {
-        let new_pos = old_pos.direction(dir);
+    fn push(&self, rock: Rock, old_pos: Point, dir: Direction) -> Point {
+        let new_pos = match dir {
+            Direction::Left => old_pos + Point::LEFT,
+            Direction::Right => old_pos + Point::RIGHT,
+        };
 
         if self.collision(rock, new_pos) {
             old_pos
@@ -206,8 +176,8 @@ impl Board {
 
     /// fall returns the position of the rock falling one square down, or None if the rock
     /// has reached the floor or is on top of another rock.
-    fn fall(&self, rock: Rock, old_pos: Position) -> Option<Position> {
-        let new_pos = old_pos + Position::at(-1, 0);
+    fn fall(&self, rock: Rock, old_pos: Point) -> Option<Point> {
+        let new_pos = old_pos + Point::at(-1, 0);
 
         if self.collision(rock, new_pos) {
             None
@@ -217,7 +187,7 @@ impl Board {
     }
 
     /// end records the position of the rock on the board
-    fn end(&mut self, rock: Rock, pos: Position) {
+    fn end(&mut self, rock: Rock, pos: Point) {
         // make sure there's enough rows on the board for the rock.
         let rock_top = match rock {
             Rock::Dash => pos.row,
@@ -225,78 +195,138 @@ impl Board {
             Rock::L => pos.row + 2,
             Rock::I => pos.row,
             Rock::Square => pos.row,
-        } as usize;
+        };
 
-        for _ in self.rock_rows.len()..=rock_top {
-            self.rock_rows.push([false; 7])
-        }
+        self.rock_rows.grow(rock_top as usize + 1, false);
 
         // record the rock positions.
-        for p in rock.positions(&pos) {
-            self.rock_rows[p.row as usize][p.col as usize] = true
+        for p in rock.positions(pos) {
+            if let Some(cell) = self.rock_rows.get_mut(p) {
+                *cell = true
+            }
         }
     }
 
     /// collision returns whether the rock is out of bounds or
     /// overlaps with another rock on the board.
-    fn collision(&self, rock: Rock, pos: Position) -> bool {
-        !rock.positions(&pos).iter().all(|p| self.empty(p))
+    fn collision(&self, rock: Rock, pos: Point) -> bool {
+        !rock.positions(pos).iter().all(|p| self.empty(p))
     }
 
     /// empty returns whether the given position is empty on this board.
-    fn empty(&self, pos: &Position) -> bool {
+    fn empty(&self, pos: &Point) -> bool {
         if pos.row < 0 || pos.col < 0 || pos.col >= 7 {
             return false;
         }
 
-        if pos.row >= self.len() {
-            return true;
+        // rows above the highest rock are empty.
+        !self.rock_rows.get(*pos).copied().unwrap_or(false)
+    }
+
+    /// drop lets the rock fall from its starting position until it comes to rest, pushed by the
+    /// wind from the given index into the directions.  It returns the index of the next wind.
+    pub(crate) fn drop(&mut self, rock: Rock, directions: &[Direction], mut wind: usize) -> usize {
+        let mut pos = self.start(rock);
+
+        loop {
+            // rock is pushed by the wind, then falls downward.
+            pos = self.push(rock, pos, directions[wind]);
+            wind = (wind + 1) % directions.len();
+
+            // rock falls until it's downward movement is blocked.
+            match self.fall(rock, pos) {
+                Some(fall_pos) => pos = fall_pos,
+                None => {
+                    self.end(rock, pos);
+                    return wind
+                },
+            }
         }
+    }
+
+    /// surface returns how far below the top of the board the highest rock in each column is,
+    /// or the height of the board if the column has no rocks.  Depths stop at SURFACE_DEPTH, so
+    /// a column that no rock ever lands in doesn't stop the surface repeating.
+    fn surface(&self) -> [i64; 7] {
+        let mut surface = [self.len().min(SURFACE_DEPTH); 7];
 
-        !self.rock_rows[pos.row as usize][pos.col as usize]
+        for (col, depth) in surface.iter_mut().enumerate() {
+            if let Some(row) = self.rock_rows.rows().rev().take(SURFACE_DEPTH as usize).position(|rocks| rocks[col]) {
+                *depth = row as i64;
+            }
+        }
+
+        surface
     }
 
     /// len returns the number of rows in this board that have rocks.
-    fn len(&self) -> isize {
-        self.rock_rows.len() as isize
+    pub(crate) fn len(&self) -> i64 {
+        self.rock_rows.height() as i64
     }
 }
 
-fn load_directions(filename: &str) -> Vec<Direction> {
-    let f = File::open(filename);
-    let f = BufReader::new(f.unwrap());
+/// load_directions parses the jet pattern, which is a single line of '<' and '>'.
+pub fn load_directions(input: &str) -> Result<Vec<Direction>> {
+    let line = input.lines().next().unwrap_or("");
+    if line.is_empty() {
+        return Err(Error::parse("", "missing jet pattern"));
+    }
 
-    f.lines().flatten().next().unwrap().chars().map(|c| Direction::from(c)).collect_vec()
+    line.chars()
+        .map(Direction::try_from)
+        .collect::<Result<Vec<Direction>>>()
+        .map_err(|err| err.at_line(1))
 }
 
+/// SURFACE_DEPTH is how far down the surface of the tower is remembered.  A rock that falls
+/// further than this down a shaft can make two states look the same when they aren't, so a
+/// repeated state is only trusted once the tower has grown the same way for a whole period.
+const SURFACE_DEPTH: i64 = 64;
+
 /// height_after_rocks returns the height of the tower after the given number of rocks have fallen.
-fn height_after_rocks(directions: &Vec<Direction>, rocks: usize) -> isize {
-    let mut wind = directions.into_iter().cycle();
-    let mut rock_order = Rock::order().into_iter().cycle();
+/// The rocks and wind eventually settle into a cycle, which is found by remembering the rock,
+/// wind and surface of the tower after each rock falls.  Once a state repeats, and each rock of
+/// the last period raised the tower as much as the rock a period before it, the remaining whole
+/// cycles are skipped by adding their height instead of simulating them.
+pub fn height_after_rocks(directions: &[Direction], rocks: usize) -> i64 {
+    let rock_order = Rock::order();
     let mut board = Board::new();
+    let mut wind = 0;
 
-    for _ in 0..rocks {
-        let rock = rock_order.next().unwrap();
-        let mut pos = board.start(rock);
+    let mut seen: HashMap<(usize, usize, [i64; 7]), usize> = HashMap::new();
+    let mut heights = vec![0];
+    let mut skipped_height = 0;
 
-        let mut falling = true;
-        while falling {
-            // rock is pushed by the wind, then falls downward.
-            let wind_dir = wind.next().unwrap();
-            pos = board.push(rock, pos, *wind_dir);
+    let mut fallen = 0;
+    while fallen < rocks {
+        let rock_idx = fallen % rock_order.len();
+        wind = board.drop(rock_order[rock_idx], directions, wind);
+        fallen += 1;
 
-            // rock falls until it's downward movement is blocked.
-            let maybe_fall_pos = board.fall(rock, pos);
-            if let Some(fall_pos) = maybe_fall_pos {
-                pos = fall_pos;
-            } else {
-                board.end(rock, pos);
-                falling = false;
+        if skipped_height > 0 {
+            continue
+        }
+
+        heights.push(board.len());
+
+        let state = (rock_idx, wind, board.surface());
+        if let Some(prev_fallen) = seen.insert(state, fallen) {
+            let period = fallen - prev_fallen;
+            let growth = heights[fallen] - heights[prev_fallen];
+
+            let repeats = fallen >= 2 * period
+                && (fallen - period..fallen).all(|i| heights[i] - heights[i - period] == growth);
+
+            if repeats {
+                let cycles = (rocks - fallen) / period;
+
+                skipped_height = cycles as i64 * growth;
+                fallen += cycles * period;
             }
         }
     }
 
-    board.len()
+    board.len() + skipped_height
 }
 
 #[cfg(test)]
@@ -305,8 +335,13 @@ mod tests {
 
     #[test]
     fn test_height_after_rocks() {
-        let directions = load_directions("input/day17_sample.txt");
+        let directions = Day17::parse(include_str!("../input/day17_sample.txt")).unwrap();
 
         assert_eq!(3068, height_after_rocks(&directions, 2022));
+        assert_eq!(1514285714288, height_after_rocks(&directions, 1000000000000));
+
+        // rocks never reach the right wall, so its column stays empty all the way down.
+        let directions = load_directions("<>").unwrap();
+        assert_eq!(4 * height_after_rocks(&directions, 250), height_after_rocks(&directions, 1000));
     }
 }
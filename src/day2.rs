@@ -1,151 +1,327 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::str::FromStr;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let rounds = load_rounds("input/day2.txt");
+use itertools::Itertools;
+use toml::{Table, Value};
 
-    println!("Day 2");
-    println!("Part 1: {}", total_score(&rounds, Round::shape_points));
-    println!("Part 2: {}", total_score(&rounds, Round::outcome_points));
+use crate::error::{Error, parse_lines, Result};
+use crate::solution::Solution;
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = (Game, Vec<Round>);
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let game = Game::parse(ROCK_PAPER_SCISSORS)?;
+        let rounds = game.load_rounds(input)?;
+
+        Ok((game, rounds))
+    }
+
+    fn part1((game, rounds): &Self::Input) -> Result<Self::Part1> {
+        total_score(rounds, |round| round.shape_points(game))
+    }
+
+    fn part2((game, rounds): &Self::Input) -> Result<Self::Part2> {
+        total_score(rounds, |round| round.outcome_points(game))
+    }
 }
 
+/// ROCK_PAPER_SCISSORS is the spec of the game in the puzzle.
+pub const ROCK_PAPER_SCISSORS: &str = r#"
+shapes = ["rock", "paper", "scissors"]
+shape_points = [1, 2, 3]
+opponent_codes = ["A", "B", "C"]
+shape_codes = ["X", "Y", "Z"]
+
+# loss, tie, win
+outcome_points = [0, 3, 6]
+outcome_codes = ["X", "Y", "Z"]
+"#;
+
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
-enum Shape {
-    Rock, Paper, Scissors
+enum Outcome {
+    Loss, Tie, Win
+}
+
+impl Outcome {
+    /// ALL are the outcomes in the order a spec lists their points and codes.
+    const ALL: [Outcome; 3] = [Outcome::Loss, Outcome::Tie, Outcome::Win];
+}
+
+/// Game is a cyclic hand game like rock paper scissors, with any odd number of shapes.  Shapes
+/// are listed in a cycle where each beats the shapes an odd number of places before it, so
+/// every shape beats half of the others.  Rock paper scissors lizard Spock is:
+///
+/// ```toml
+/// shapes = ["rock", "paper", "scissors", "spock", "lizard"]
+/// ```
+pub struct Game {
+    shapes: Vec<String>,
+    shape_points: Vec<i32>,
+    opponent_codes: Vec<String>,
+    shape_codes: Vec<String>,
+    outcome_points: Vec<i32>,
+    outcome_codes: Vec<String>,
 }
 
-impl Shape {
-    /// parse returns a shape for the given code.
-    fn parse(value: &str) -> Self {
-        match value {
-            "A" | "X" => Shape::Rock,
-            "B" | "Y" => Shape::Paper,
-            "C" | "Z" => Shape::Scissors,
-            _ => panic!("Invalid shape: {}", value)
+impl Game {
+    /// parse parses a game from a TOML spec like ROCK_PAPER_SCISSORS.  The shape points and
+    /// codes are listed in the same order as the shapes, and the outcome points and codes are
+    /// listed for a loss, a tie and a win.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let table: Table = toml::from_str(spec).map_err(|err| Error::parse("", err.message()))?;
+
+        let list = |key: &str| match table.get(key) {
+            Some(Value::Array(values)) => Ok(values),
+            _ => Err(Error::parse(key, "expected a list")),
+        };
+
+        let strings = |key: &str| list(key)?.iter()
+            .map(|value| value.as_str().map(str::to_string).ok_or_else(|| Error::parse(key, "expected a list of strings")))
+            .collect::<Result<Vec<String>>>();
+
+        let points = |key: &str| list(key)?.iter()
+            .map(|value| value.as_integer().and_then(|num| i32::try_from(num).ok()).ok_or_else(|| Error::parse(key, "expected a list of points")))
+            .collect::<Result<Vec<i32>>>();
+
+        let game = Game {
+            shapes: strings("shapes")?,
+            shape_points: points("shape_points")?,
+            opponent_codes: strings("opponent_codes")?,
+            shape_codes: strings("shape_codes")?,
+            outcome_points: points("outcome_points")?,
+            outcome_codes: strings("outcome_codes")?,
+        };
+
+        if game.shapes.len().is_multiple_of(2) {
+            return Err(Error::parse("shapes", "there must be an odd number of shapes"));
+        }
+
+        for (key, len) in [
+            ("shape_points", game.shape_points.len()),
+            ("opponent_codes", game.opponent_codes.len()),
+            ("shape_codes", game.shape_codes.len()),
+        ] {
+            if len != game.shapes.len() {
+                return Err(Error::parse(key, "expected one for each shape"));
+            }
         }
-    }
 
-    /// loss returns the shape that beats this shape.
-    fn loss(self) -> Self {
-        match self {
-            Shape::Rock => Shape::Paper,
-            Shape::Paper => Shape::Scissors,
-            Shape::Scissors => Shape::Rock,
+        for (key, len) in [("outcome_points", game.outcome_points.len()), ("outcome_codes", game.outcome_codes.len())] {
+            if len != Outcome::ALL.len() {
+                return Err(Error::parse(key, "expected one for a loss, a tie and a win"));
+            }
         }
+
+        for (key, codes) in [
+            ("opponent_codes", &game.opponent_codes),
+            ("shape_codes", &game.shape_codes),
+            ("outcome_codes", &game.outcome_codes),
+        ] {
+            if !codes.iter().all_unique() {
+                return Err(Error::parse(key, "codes must be different"));
+            }
+        }
+
+        Ok(game)
     }
 
-    /// tie returns the shape that this shape ties with.
-    fn tie(self) -> Self {
-        return self.clone()
+    /// shape returns the name of the shape with the given index.
+    pub fn shape(&self, shape: usize) -> &str {
+        &self.shapes[shape]
     }
 
-    /// win returns the shape that this shape beats.
-    fn win(self) -> Self {
-        match self {
-            Shape::Rock => Shape::Scissors,
-            Shape::Paper => Shape::Rock,
-            Shape::Scissors => Shape::Paper,
+    /// outcome returns the outcome of you playing a shape against an opponent.
+    fn outcome(&self, you: usize, opponent: usize) -> Outcome {
+        match (you + self.shapes.len() - opponent) % self.shapes.len() {
+            0 => Outcome::Tie,
+            places if places % 2 == 1 => Outcome::Win,
+            _ => Outcome::Loss,
         }
     }
 
-    /// points returns the number of points this shape is worth if you play it.
-    fn points(&self) -> i32 {
-        match self {
-            Shape::Rock => 1,
-            Shape::Paper => 2,
-            Shape::Scissors => 3,
-        }
+    /// play returns the shape you should play to reach an outcome against the opponent.  In
+    /// games with more than three shapes there's more than one, so it's the one worth the most.
+    fn play(&self, outcome: Outcome, opponent: usize) -> usize {
+        (0..self.shapes.len())
+            .filter(|&you| self.outcome(you, opponent) == outcome)
+            .max_by_key(|&you| self.shape_points[you])
+            .expect("every outcome can be reached")
     }
-}
 
-#[derive(Debug, Eq, PartialEq)]
-enum Outcome {
-    Loss, Win, Tie
-}
+    /// points returns the number of points you score playing a shape for an outcome.
+    fn points(&self, you: usize, outcome: Outcome) -> i32 {
+        self.shape_points[you] + self.outcome_points[outcome as usize]
+    }
 
-impl Outcome {
-    /// parse converts the given code into a desired outcome.
-    fn parse(value: &str) -> Self {
-        match value {
-            "X" => Outcome::Loss,
-            "Y" => Outcome::Tie,
-            "Z" => Outcome::Win,
-            _ => panic!("Invalid outcome: {}", value)
-        }
+    /// best_response returns the shape that scores the most against the opponent's shape, and
+    /// the first listed if more than one does.
+    pub fn best_response(&self, opponent: usize) -> usize {
+        (0..self.shapes.len())
+            .rev()
+            .max_by_key(|&you| self.points(you, self.outcome(you, opponent)))
+            .expect("there's always a shape")
     }
 
-    /// play returns the shape you should play to reach this outcome against the opponent.
-    fn play(&self, opponent: Shape) -> Shape {
-        match self {
-            Outcome::Loss => opponent.win(),
-            Outcome::Tie => opponent.tie(),
-            Outcome::Win => opponent.loss(),
+    /// opponent_moves returns how many times the opponent plays each shape in the rounds.
+    pub fn opponent_moves(&self, rounds: &[Round]) -> Result<Vec<usize>> {
+        let mut moves = vec![0; self.shapes.len()];
+
+        for round in rounds {
+            moves[position(&self.opponent_codes, &round.opponent, "an opponent's shape")?] += 1;
         }
+
+        Ok(moves)
     }
 
-    /// of returns the outcome of you playing a shape against an opponent.
-    fn of(you: Shape, opponent: Shape) -> Outcome {
-        match opponent {
-            _ if opponent == you.tie() => Outcome::Tie,
-            _ if opponent == you.win() => Outcome::Win,
-            _ if opponent == you.loss() => Outcome::Loss,
-            _ => panic!("win, loss, tie tables are incorrect"),
+    /// expected_score returns the average score of a round where the opponent plays shapes as
+    /// often as in opponent_moves, and you play each shape with the probability in strategy.
+    pub fn expected_score(&self, opponent_moves: &[usize], strategy: &[f64]) -> Result<f64> {
+        let total = strategy.iter().sum::<f64>();
+        if strategy.len() != self.shapes.len() || strategy.iter().any(|&p| p < 0.0) || (total - 1.0).abs() > 1e-9 {
+            return Err(Error::parse(&format!("{:?}", strategy), "a strategy needs a probability for each shape, adding up to 1"));
+        }
+
+        let rounds = opponent_moves.iter().sum::<usize>();
+        if rounds == 0 {
+            return Ok(0.0);
         }
+
+        let score = opponent_moves.iter().enumerate().flat_map(|(opponent, &times)| {
+            strategy.iter().enumerate().map(move |(you, p)| {
+                times as f64 * p * self.points(you, self.outcome(you, opponent)) as f64
+            })
+        }).sum::<f64>();
+
+        Ok(score / rounds as f64)
     }
 
-    /// points returns the number of points this round is worth.
-    fn points(&self) -> i32 {
-        match self {
-            Outcome::Loss => 0,
-            Outcome::Tie => 3,
-            Outcome::Win => 6,
+    /// analyze compares a strategy guide with the best responses to the opponent's moves.
+    pub fn analyze(&self, rounds: &[Round]) -> Result<Analysis> {
+        let opponent_moves = self.opponent_moves(rounds)?;
+        let best_responses = (0..self.shapes.len()).map(|opponent| self.best_response(opponent)).collect_vec();
+
+        let optimal_score = opponent_moves.iter().enumerate()
+            .map(|(opponent, &times)| {
+                let you = best_responses[opponent];
+                times as i32 * self.points(you, self.outcome(you, opponent))
+            })
+            .sum();
+
+        Ok(Analysis {
+            opponent_moves,
+            best_responses,
+            optimal_score,
+            shape_score: total_score(rounds, |round| round.shape_points(self)).ok(),
+            outcome_score: total_score(rounds, |round| round.outcome_points(self)).ok(),
+        })
+    }
+
+    /// load_rounds parses rounds played in this game out of the given input.  Your code can
+    /// mean a shape or an outcome, so it only has to be one of them.
+    pub fn load_rounds(&self, input: &str) -> Result<Vec<Round>> {
+        let rounds: Vec<Round> = parse_lines(input)?;
+
+        for (i, round) in rounds.iter().enumerate() {
+            let line = || format!("{} {}", round.opponent, round.you);
+
+            if !self.opponent_codes.contains(&round.opponent) {
+                return Err(Error::parse(&line(), "invalid opponent shape").at_line(i + 1));
+            }
+
+            if !self.shape_codes.contains(&round.you) && !self.outcome_codes.contains(&round.you) {
+                return Err(Error::parse(&line(), "invalid shape or outcome").at_line(i + 1));
+            }
         }
+
+        Ok(rounds)
     }
 }
 
-struct Round {
+/// Analysis is how a strategy guide compares with playing the best response to every move.
+#[derive(Debug, PartialEq)]
+pub struct Analysis {
+    /// opponent_moves are how many times the opponent plays each shape.
+    pub opponent_moves: Vec<usize>,
+
+    /// best_responses are the shapes that score the most against each shape.
+    pub best_responses: Vec<usize>,
+
+    /// optimal_score is the total score of playing the best response every round.
+    pub optimal_score: i32,
+
+    /// shape_score is the guide's total score if its codes are shapes, or None if they aren't
+    /// all shape codes.
+    pub shape_score: Option<i32>,
+
+    /// outcome_score is the guide's total score if its codes are outcomes, or None if they
+    /// aren't all outcome codes.
+    pub outcome_score: Option<i32>,
+}
+
+impl Analysis {
+    /// shape_gap returns how many points the guide misses out on if its codes are shapes.
+    pub fn shape_gap(&self) -> Option<i32> {
+        self.shape_score.map(|score| self.optimal_score - score)
+    }
+
+    /// outcome_gap returns how many points the guide misses out on if its codes are outcomes.
+    pub fn outcome_gap(&self) -> Option<i32> {
+        self.outcome_score.map(|score| self.optimal_score - score)
+    }
+}
+
+/// position returns the index of code in codes, or an error explaining that it isn't a code for
+/// what.
+fn position(codes: &[String], code: &str, what: &str) -> Result<usize> {
+    codes.iter().position(|c| c == code).ok_or_else(|| Error::parse(code, format!("not a code for {}", what)))
+}
+
+pub struct Round {
     you: String,
     opponent: String,
 }
 
+impl FromStr for Round {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        // Rounds look like 'A Y'
+        match s.split_whitespace().collect::<Vec<&str>>().as_slice() {
+            [opponent, you] => Ok(Round {
+                opponent: opponent.to_string(),
+                you: you.to_string(),
+            }),
+            _ => Err(Error::parse(s, "invalid round")),
+        }
+    }
+}
+
 impl Round {
     /// shape_points returns the number of points that you score in this round where your
     /// guide is the shape you should play.
-    fn shape_points(&self) -> i32 {
-        let (you, opponent) = (Shape::parse(&self.you), Shape::parse(&self.opponent));
+    pub fn shape_points(&self, game: &Game) -> Result<i32> {
+        let opponent = position(&game.opponent_codes, &self.opponent, "an opponent's shape")?;
+        let you = position(&game.shape_codes, &self.you, "your shape")?;
 
-        you.points() + Outcome::of(you, opponent).points()
+        Ok(game.points(you, game.outcome(you, opponent)))
     }
 
-    /// shape_points returns the number of points that you score in this round where your
+    /// outcome_points returns the number of points that you score in this round where your
     /// guide is the outcome of the round.
-    fn outcome_points(&self) -> i32 {
-        let (opponent, outcome) = (Shape::parse(&self.opponent), Outcome::parse(&self.you));
-        let you = outcome.play(opponent);
+    pub fn outcome_points(&self, game: &Game) -> Result<i32> {
+        let opponent = position(&game.opponent_codes, &self.opponent, "an opponent's shape")?;
+        let outcome = Outcome::ALL[position(&game.outcome_codes, &self.you, "an outcome")?];
 
-        you.points() + outcome.points()
+        Ok(game.points(game.play(outcome, opponent), outcome))
     }
 }
 
-/// load_rounds parses rounds out of the given file.  Lines contain an opponent's move and your move,
-/// and the meaning of your move changes with the problem parts.
-fn load_rounds(filename: &str) -> Vec<Round> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
-
-    f.lines().flatten().map(|line| {
-        let mut shapes = line.split_whitespace();
-
-        Round {
-            opponent: shapes.next().unwrap().to_string(),
-            you: shapes.next().unwrap().to_string()
-        }
-    }).collect()
-}
-
 /// total_score returns the sum of scores in all rounds, using the given scoring function.
-fn total_score(rounds: &Vec<Round>, round_score: impl Fn(&Round) -> i32) -> i32 {
+pub fn total_score(rounds: &[Round], round_score: impl Fn(&Round) -> Result<i32>) -> Result<i32> {
     rounds.iter().map(round_score).sum()
 }
 
@@ -155,15 +331,59 @@ mod tests {
 
     #[test]
     fn test_total_score_shape() {
-        let rounds = load_rounds("input/day2_sample.txt");
+        let (game, rounds) = Day2::parse(include_str!("../input/day2_sample.txt")).unwrap();
 
-        assert_eq!(15, total_score(&rounds, Round::shape_points));
+        assert_eq!(15, total_score(&rounds, |round| round.shape_points(&game)).unwrap());
     }
 
     #[test]
     fn test_total_score_outcome() {
-        let rounds = load_rounds("input/day2_sample.txt");
+        let (game, rounds) = Day2::parse(include_str!("../input/day2_sample.txt")).unwrap();
+
+        assert_eq!(12, total_score(&rounds, |round| round.outcome_points(&game)).unwrap());
+    }
+
+    #[test]
+    fn test_analyze() {
+        let (game, rounds) = Day2::parse(include_str!("../input/day2_sample.txt")).unwrap();
+
+        let analysis = game.analyze(&rounds).unwrap();
+        assert_eq!(vec![1, 1, 1], analysis.opponent_moves);
+        assert_eq!(vec!["paper", "scissors", "rock"], analysis.best_responses.iter().map(|&you| game.shape(you)).collect_vec());
+        assert_eq!(8 + 9 + 7, analysis.optimal_score);
+        assert_eq!((Some(9), Some(12)), (analysis.shape_gap(), analysis.outcome_gap()));
+
+        // against every shape equally, each shape scores its points plus a tie on average.
+        assert_eq!(5.0, game.expected_score(&analysis.opponent_moves, &[0.0, 1.0, 0.0]).unwrap());
+        assert_eq!(4.5, game.expected_score(&analysis.opponent_moves, &[0.5, 0.5, 0.0]).unwrap());
+        assert_eq!(8.0, game.expected_score(&[1, 0, 0], &[0.0, 1.0, 0.0]).unwrap());
+        assert!(game.expected_score(&analysis.opponent_moves, &[0.5, 0.6, 0.0]).is_err());
+    }
+
+    #[test]
+    fn test_rock_paper_scissors_lizard_spock() {
+        let game = Game::parse(r#"
+            shapes = ["rock", "paper", "scissors", "spock", "lizard"]
+            shape_points = [1, 2, 3, 4, 5]
+            opponent_codes = ["A", "B", "C", "D", "E"]
+            shape_codes = ["V", "W", "X", "Y", "Z"]
+            outcome_points = [0, 3, 6]
+            outcome_codes = ["X", "Y", "Z"]
+        "#).unwrap();
+
+        // spock vaporizes rock, and lizard is crushed by scissors.
+        let rounds = game.load_rounds("A Y\nC Z\n").unwrap();
+        assert_eq!(4 + 6 + 5, total_score(&rounds, |round| round.shape_points(&game)).unwrap());
+
+        // lizard is worth the most of the shapes rock beats, and spock of those that beat scissors.
+        let rounds = game.load_rounds("A X\nC Z\n").unwrap();
+        assert_eq!(5 + 4 + 6, total_score(&rounds, |round| round.outcome_points(&game)).unwrap());
+
+        // V is a shape but not an outcome.
+        let rounds = game.load_rounds("A V\n").unwrap();
+        assert!(total_score(&rounds, |round| round.outcome_points(&game)).is_err());
 
-        assert_eq!(12, total_score(&rounds, Round::outcome_points));
+        assert!(game.load_rounds("F X\n").is_err());
+        assert!(Game::parse(&ROCK_PAPER_SCISSORS.replace(r#""C"]"#, r#""C", "D"]"#)).is_err());
     }
 }
@@ -1,19 +1,31 @@
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use std::str::FromStr;
-use std::string::ParseError;
 use itertools::Itertools;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let monkeys = load_monkeys("input/day21.txt");
+use crate::error::{Error, parse_num, Result};
+use crate::solution::Solution;
 
-    println!("Day 21");
-    println!("Part 1: {}", root_yell(&monkeys));
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = HashMap<String, Yell>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        load_monkeys(input)
+    }
+
+    fn part1(monkeys: &Self::Input) -> Result<Self::Part1> {
+        root_yell(monkeys)
+    }
+
+    fn part2(monkeys: &Self::Input) -> Result<Self::Part2> {
+        humn_yell(monkeys)
+    }
 }
 
-enum Yell {
+pub enum Yell {
     Number(i64),
     Plus(String, String),
     Minus(String, String),
@@ -21,60 +33,197 @@ enum Yell {
     Divide(String, String),
 }
 
-fn yell_value(monkey: &String, monkeys: &HashMap<String, Yell>) -> i64 {
+/// yell_value returns the number the given monkey yells, or an error if it's too big to store
+/// or divides by zero.
+fn yell_value(monkey: &String, monkeys: &HashMap<String, Yell>) -> Result<i64> {
     let yell = &monkeys[monkey];
 
-    match yell {
-        Yell::Number(num) => *num,
-        Yell::Plus(a, b) => yell_value(a, monkeys) + yell_value(b, monkeys),
-        Yell::Minus(a, b) => yell_value(a, monkeys) - yell_value(b, monkeys),
-        Yell::Multiply(a, b) => yell_value(a, monkeys) * yell_value(b, monkeys),
-        Yell::Divide(a, b) => yell_value(a, monkeys) / yell_value(b, monkeys),
+    let value = match yell {
+        Yell::Number(num) => Some(*num),
+        Yell::Plus(a, b) => yell_value(a, monkeys)?.checked_add(yell_value(b, monkeys)?),
+        Yell::Minus(a, b) => yell_value(a, monkeys)?.checked_sub(yell_value(b, monkeys)?),
+        Yell::Multiply(a, b) => yell_value(a, monkeys)?.checked_mul(yell_value(b, monkeys)?),
+        Yell::Divide(a, b) => yell_value(a, monkeys)?.checked_div(yell_value(b, monkeys)?),
+    };
+
+    value.ok_or_else(|| Error::solve(format!("{}'s yell is too big to store, or divides by zero", monkey)))
+}
+
+impl Yell {
+    /// operands returns the monkeys this yell depends on.
+    pub fn operands(&self) -> Vec<&String> {
+        match self {
+            Yell::Number(_) => vec![],
+            Yell::Plus(a, b) | Yell::Minus(a, b) | Yell::Multiply(a, b) | Yell::Divide(a, b) => vec![a, b],
+        }
     }
 }
 
 impl FromStr for Yell {
-    type Err = ParseError;
+    type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> Result<Self> {
         // yells look like '5', or 'drzm * dbpl'
-        if !s.contains(" ") {
-            return Ok(Yell::Number(s.parse().unwrap()))
+        if !s.contains(' ') {
+            return Ok(Yell::Number(parse_num(s)?))
         }
 
-        let parts = s.split_whitespace().collect_vec();
+        match s.split_whitespace().collect_vec().as_slice() {
+            [a, "+", b] => Ok(Yell::Plus(a.to_string(), b.to_string())),
+            [a, "-", b] => Ok(Yell::Minus(a.to_string(), b.to_string())),
+            [a, "*", b] => Ok(Yell::Multiply(a.to_string(), b.to_string())),
+            [a, "/", b] => Ok(Yell::Divide(a.to_string(), b.to_string())),
+            _ => Err(Error::parse(s, "invalid yell")),
+        }
+    }
+}
 
-        match parts[1] {
-            "+" => Ok(Yell::Plus(parts[0].to_string(), parts[2].to_string())),
-            "-" => Ok(Yell::Minus(parts[0].to_string(), parts[2].to_string())),
-            "*" => Ok(Yell::Multiply(parts[0].to_string(), parts[2].to_string())),
-            "/" => Ok(Yell::Divide(parts[0].to_string(), parts[2].to_string())),
-            _ => panic!("Invalid yell: {}", s),
+/// load_monkeys parses monkeys and what they yell, which look like 'root: pppw + sjmn'.
+/// Every monkey that's referred to in a yell must exist, including root.
+pub fn load_monkeys(input: &str) -> Result<HashMap<String, Yell>> {
+    let mut monkeys = HashMap::new();
+
+    for (i, line) in input.lines().enumerate() {
+        let (name, yell) = line.split_once(": ").ok_or_else(|| Error::parse(line, "invalid monkey"))
+            .and_then(|(name, yell)| Ok((name, yell.parse::<Yell>()?)))
+            .map_err(|err| err.at_line(i + 1))?;
+
+        monkeys.insert(name.to_string(), (i + 1, yell));
+    }
+
+    for (line, yell) in monkeys.values() {
+        if let Some(missing) = yell.operands().into_iter().find(|monkey| !monkeys.contains_key(*monkey)) {
+            return Err(Error::parse(missing, "no such monkey").at_line(*line));
         }
     }
+
+    if !monkeys.contains_key("root") {
+        return Err(Error::parse("", "missing root monkey"));
+    }
+
+    check_cycles(&monkeys)?;
+
+    Ok(monkeys.into_iter().map(|(name, (_, yell))| (name, yell)).collect())
 }
 
-fn load_monkeys(filename: &str) -> HashMap<String, Yell> {
-    let f = File::open(filename);
-    let f = BufReader::new(f.unwrap());
+/// check_cycles returns an error if any monkey's yell depends on itself, which would never
+/// finish being worked out.  Monkeys are resolved once everything they depend on has been, so
+/// any that are left over are on or after a cycle.
+fn check_cycles(monkeys: &HashMap<String, (usize, Yell)>) -> Result<()> {
+    let mut waiting = HashMap::new();
+    let mut dependents: HashMap<&String, Vec<&String>> = HashMap::new();
+    let mut resolved = Vec::new();
+
+    for (name, (_, yell)) in monkeys {
+        let operands = yell.operands().into_iter().unique().collect_vec();
+        for operand in &operands {
+            dependents.entry(operand).or_default().push(name);
+        }
+
+        match operands.len() {
+            0 => resolved.push(name),
+            len => { waiting.insert(name, len); },
+        }
+    }
 
-    f.lines().flatten().map(|line| {
-        let mut parts = line.split(": ");
-        (parts.next().unwrap().to_string(), parts.next().unwrap().parse().unwrap())
-    }).collect()
+    while let Some(name) = resolved.pop() {
+        for dependent in dependents.get(name).into_iter().flatten() {
+            let count = waiting.get_mut(dependent).unwrap();
+            *count -= 1;
+
+            if *count == 0 {
+                waiting.remove(dependent);
+                resolved.push(dependent);
+            }
+        }
+    }
+
+    match waiting.keys().min_by_key(|name| monkeys[**name].0) {
+        Some(name) => Err(Error::parse(name, "yell depends on a cycle of monkeys").at_line(monkeys[*name].0)),
+        None => Ok(()),
+    }
 }
 
-fn root_yell(monkeys: &HashMap<String, Yell>) -> i64 {
+/// root_yell returns the number the root monkey yells.
+pub fn root_yell(monkeys: &HashMap<String, Yell>) -> Result<i64> {
     yell_value(&"root".to_string(), monkeys)
 }
 
+/// humn_yell returns the number the human must yell for both of the root monkey's operands to
+/// be equal.
+pub fn humn_yell(monkeys: &HashMap<String, Yell>) -> Result<i64> {
+    match monkeys["root"].operands().as_slice() {
+        [a, b] if has_humn(a, monkeys) => solve_humn(a, yell_value(b, monkeys)?, monkeys),
+        [a, b] => solve_humn(b, yell_value(a, monkeys)?, monkeys),
+        _ => Err(Error::solve("root must have two operands")),
+    }
+}
+
+/// has_humn returns whether the human is the given monkey, or one of the monkeys it depends on.
+fn has_humn(monkey: &String, monkeys: &HashMap<String, Yell>) -> bool {
+    monkey == "humn" || monkeys[monkey].operands().into_iter().any(|m| has_humn(m, monkeys))
+}
+
+/// solve_humn returns the number the human must yell for the given monkey to yell the target.
+/// The operation of each monkey on the way to the human is inverted, using the value of the
+/// operand that doesn't depend on the human.
+fn solve_humn(monkey: &String, target: i64, monkeys: &HashMap<String, Yell>) -> Result<i64> {
+    if monkey == "humn" {
+        return Ok(target)
+    }
+
+    let yell = &monkeys[monkey];
+    let (unknown, known, humn_left) = match yell.operands().as_slice() {
+        [a, b] if has_humn(a, monkeys) => (*a, yell_value(b, monkeys)?, true),
+        [a, b] => (*b, yell_value(a, monkeys)?, false),
+        _ => return Err(Error::solve("humn isn't one of the monkeys root depends on")),
+    };
+
+    let exact_div = |a: i64, b: i64| {
+        if b == 0 || a.checked_rem(b) != Some(0) {
+            return Err(Error::solve(format!("humn can't be found - {}'s {} isn't divisible by {}", monkey, a, b)));
+        }
+        Ok(a / b)
+    };
+
+    let target = match (yell, humn_left) {
+        (Yell::Plus(..), _) => target.checked_sub(known),
+        (Yell::Minus(..), true) => target.checked_add(known),
+        (Yell::Minus(..), false) => known.checked_sub(target),
+        (Yell::Multiply(..), _) => Some(exact_div(target, known)?),
+        (Yell::Divide(..), true) => target.checked_mul(known),
+        (Yell::Divide(..), false) => Some(exact_div(known, target)?),
+        (Yell::Number(_), _) => unreachable!(),
+    };
+    let target = target.ok_or_else(|| Error::solve(format!("humn's yell is too big to store, solving for {}", monkey)))?;
+
+    solve_humn(unknown, target, monkeys)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_root_yell() {
-        let monkeys = load_monkeys("input/day21_sample.txt");
-        assert_eq!(152, root_yell(&monkeys));
+        let monkeys = Day21::parse(include_str!("../input/day21_sample.txt")).unwrap();
+        assert_eq!(152, root_yell(&monkeys).unwrap());
+
+        let monkeys = load_monkeys("root: aaaa * aaaa\naaaa: 5000000000000\n").unwrap();
+        assert!(root_yell(&monkeys).is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_humn_yell() {
+        let monkeys = Day21::parse(include_str!("../input/day21_sample.txt")).unwrap();
+        assert_eq!(301, humn_yell(&monkeys).unwrap());
+
+        // humn has to yell root's other side times aaaa, which is too big.
+        let monkeys = load_monkeys("root: pppw + sjmn\npppw: humn / aaaa\nsjmn: 4611686018427387904\naaaa: 4\nhumn: 1\n").unwrap();
+        assert!(humn_yell(&monkeys).unwrap_err().to_string().contains("too big to store"));
+
+        // monkeys waiting on each other would never yell.
+        let err = load_monkeys("root: pppw + sjmn\npppw: sjmn * humn\nsjmn: pppw - humn\nhumn: 5\n").err().unwrap();
+        assert_eq!("1: yell depends on a cycle of monkeys: 'root'", err.to_string());
+    }
+}
@@ -1,23 +1,44 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use itertools::Itertools;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let numbers = load_numbers("input/day25.txt");
+use crate::error::{Error, Result};
+use crate::registry::Part;
+use crate::solution::Solution;
 
-    println!("Day 25");
-    println!("Part 1: {}", sum_numbers(&numbers));
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Input = Vec<String>;
+    type Part1 = String;
+    type Part2 = String;
+
+    /// Day 25 only has one puzzle - the last star is awarded for finishing every other day.
+    const PARTS: Part = Part::One;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        load_numbers(input)
+    }
+
+    fn part1(numbers: &Self::Input) -> Result<Self::Part1> {
+        Ok(sum_numbers(numbers))
+    }
+
+    fn part2(_numbers: &Self::Input) -> Result<Self::Part2> {
+        Err(Error::solve("day 25 only has one puzzle"))
+    }
 }
 
-fn load_numbers(filename: &str) -> Vec<String> {
-    let f = File::open(filename);
-    let f = BufReader::new(f.unwrap());
+/// load_numbers parses SNAFU numbers, one per line.  Digits are 2, 1, 0, - and =.
+pub fn load_numbers(input: &str) -> Result<Vec<String>> {
+    input.lines().enumerate().map(|(i, line)| {
+        if line.is_empty() || !line.chars().all(|c| "210-=".contains(c)) {
+            return Err(Error::parse(line, "invalid SNAFU number").at_line(i + 1));
+        }
 
-    f.lines().flatten().collect_vec()
+        Ok(line.to_string())
+    }).collect()
 }
 
-fn from_snafu(str: &str) -> i64 {
+/// from_snafu converts a SNAFU number, with digits 2, 1, 0, - (-1) and = (-2), to an integer.
+pub fn from_snafu(str: &str) -> i64 {
     let mut num = 0;
 
     for c in str.chars() {
@@ -35,7 +56,8 @@ fn from_snafu(str: &str) -> i64 {
     num
 }
 
-fn to_snafu(num: i64) -> String {
+/// to_snafu converts an integer to a SNAFU number.
+pub fn to_snafu(num: i64) -> String {
     let mut str = String::new();
     let mut num = num;
 
@@ -57,8 +79,9 @@ fn to_snafu(num: i64) -> String {
     str
 }
 
-fn sum_numbers(numbers: &Vec<String>) -> String {
-    let sum = numbers.into_iter().map(|snafu| from_snafu(snafu.as_str())).sum();
+/// sum_numbers returns the sum of the SNAFU numbers, as a SNAFU number.
+pub fn sum_numbers(numbers: &[String]) -> String {
+    let sum = numbers.iter().map(|snafu| from_snafu(snafu.as_str())).sum();
 
     to_snafu(sum)
 }
@@ -87,7 +110,7 @@ mod tests {
             (314159265, "1121-1110-1=0"),
         ] {
             let actual_snafu = to_snafu(expected_num);
-            let actual_num = from_snafu(&expected_snafu);
+            let actual_num = from_snafu(expected_snafu);
 
             assert_eq!(expected_snafu, &actual_snafu, "snafu - {}, {}", expected_num, expected_snafu);
             assert_eq!(expected_num, actual_num, "num - {}, {}", expected_num, expected_snafu);
@@ -96,7 +119,7 @@ mod tests {
 
     #[test]
     fn test_sum_numbers() {
-        let numbers = load_numbers("input/day25_sample.txt");
+        let numbers = Day25::parse(include_str!("../input/day25_sample.txt")).unwrap();
         assert_eq!("2=-1=0", sum_numbers(&numbers))
     }
 }
@@ -1,44 +1,84 @@
-use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use itertools::Itertools;
-
-#[allow(dead_code)]
-pub fn solution() {
-    let rucksacks = parse_rucksacks("input/day3.txt");
-
-    println!("Day 3");
-    println!("Part 1: {}", total_priority(&rucksacks));
-    println!("Part 2: {}", badge_priority(&rucksacks));
-}
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+
+pub struct Day3;
+
+/// GROUP_SIZE is the number of elves in each group that shares a badge.
+pub const GROUP_SIZE: usize = 3;
+
+impl Solution for Day3 {
+    type Input = Vec<Rucksack>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_rucksacks(input)
+    }
+
+    fn part1(rucksacks: &Self::Input) -> Result<Self::Part1> {
+        total_priority(rucksacks)
+    }
 
-/// parse_rucksacks parses rucksack contents out of the given file, one per line.
-fn parse_rucksacks(filename: &str) -> Vec<Rucksack> {
-    let f = File::open(filename);
-    let f = BufReader::new(f.unwrap());
+    fn part2(rucksacks: &Self::Input) -> Result<Self::Part2> {
+        badge_priority(rucksacks, GROUP_SIZE)
+    }
+}
 
-    f.lines().flatten()
-        .map(|line| Rucksack{ contents: line })
+/// parse_rucksacks parses rucksack contents out of the given input, one per line.
+/// Items are a-z or A-Z, and both compartments hold the same number of items.
+pub fn parse_rucksacks(input: &str) -> Result<Vec<Rucksack>> {
+    input.lines().enumerate()
+        .map(|(i, line)| {
+            if !line.chars().all(|c| c.is_ascii_alphabetic()) {
+                Err(Error::parse(line, "items must be a-z or A-Z").at_line(i + 1))
+            } else if line.len() % 2 != 0 {
+                Err(Error::parse(line, "compartments must be the same size").at_line(i + 1))
+            } else {
+                let (left, right) = line.split_at(line.len() / 2);
+                Ok(Rucksack{ contents: line.to_string(), left: items(left), right: items(right) })
+            }
+        })
         .collect()
 }
 
-struct Rucksack {
-    contents: String
+/// items returns the set of the given items, as a mask with the bit for each item's priority set.
+fn items(contents: &str) -> u64 {
+    contents.chars().fold(0, |mask, item| mask | 1 << priority(item))
+}
+
+/// single returns the only item in a set, or an error about the text the set came from if
+/// there isn't exactly one.
+fn single(mask: u64, text: &str, what: &str) -> Result<char> {
+    match mask.count_ones() {
+        0 => Err(Error::parse(text, format!("no item is shared by {}", what))),
+        1 => Ok(item(mask.trailing_zeros() as i32)),
+        _ => {
+            let shared = (1..=52).filter(|&p| mask & 1 << p != 0).map(item).collect::<String>();
+            Err(Error::parse(text, format!("several items ({}) are shared by {}", shared, what)))
+        },
+    }
+}
+
+pub struct Rucksack {
+    contents: String,
+    left: u64,
+    right: u64,
 }
 
 impl Rucksack {
-    /// shared returns the item that is shared between the two halves of this rucksack.
-    fn shared_halves(&self) -> char {
-        let middle = self.contents.len() / 2;
-        let left = self.contents[..middle].chars().collect::<HashSet<char>>();
-        let right = self.contents[middle..].chars().collect::<HashSet<char>>();
+    /// shared_halves returns the item that is shared between the two halves of this rucksack.
+    pub fn shared_halves(&self) -> Result<char> {
+        single(self.left & self.right, &self.contents, "both compartments")
+    }
 
-        left.intersection(&right).at_most_one().unwrap().unwrap().to_owned()
+    /// items returns the set of items in this rucksack.
+    fn items(&self) -> u64 {
+        self.left | self.right
     }
 }
 
 /// priority returns the priority score of the given item.  a-z has 1-26, A-Z has 27-52.
-fn priority(item: char) -> i32 {
+pub fn priority(item: char) -> i32 {
     if item.is_ascii_lowercase() {
         return ((item as i32) - ('a' as i32)) + 1
     } else if item.is_ascii_uppercase() {
@@ -48,25 +88,48 @@ fn priority(item: char) -> i32 {
     panic!("Invalid item - items must be a-z or A-Z: {}", item)
 }
 
+/// item returns the item with the given priority.
+fn item(priority: i32) -> char {
+    match priority {
+        1..=26 => (b'a' + (priority - 1) as u8) as char,
+        27..=52 => (b'A' + (priority - 27) as u8) as char,
+        _ => panic!("Invalid priority - priorities must be 1-52: {}", priority),
+    }
+}
+
 /// total_priority returns the sum of the priority of the shared item in each rucksack.
-fn total_priority(rucksacks: &Vec<Rucksack>) -> i32 {
-    rucksacks.iter().map(Rucksack::shared_halves).map(priority).sum()
+pub fn total_priority(rucksacks: &[Rucksack]) -> Result<i32> {
+    rucksacks.iter().enumerate()
+        .map(|(i, rucksack)| rucksack.shared_halves().map(priority).map_err(|err| err.at_line(i + 1)))
+        .sum()
 }
 
-/// shared returns the item that is shared between all of the rucksacks.
-fn shared_badge(rucksacks: &[Rucksack]) -> char {
-    let intersection = rucksacks.iter()
-        .map(|r| r.contents.chars().collect::<HashSet<char>>())
-        .reduce(|a, b| a.intersection(&b).cloned().collect::<HashSet<char>>()).unwrap();
+/// shared_badge returns the item that is shared between all of the rucksacks.
+pub fn shared_badge(rucksacks: &[Rucksack]) -> Result<char> {
+    let shared = rucksacks.iter().fold(u64::MAX, |shared, rucksack| shared & rucksack.items());
+    let text = rucksacks.iter().map(|rucksack| rucksack.contents.as_str()).collect::<Vec<&str>>().join(" ");
 
-    intersection.into_iter().at_most_one().unwrap().unwrap().to_owned()
+    single(if rucksacks.is_empty() { 0 } else { shared }, &text, "the group")
 }
 
-/// badge_priority returns the sum of priorities of items that are shared in three-Elf groups.
-fn badge_priority(rucksacks: &Vec<Rucksack>) -> i32 {
-    rucksacks.chunks_exact(3)
-        .map(shared_badge)
-        .map(priority)
+/// badge_priority returns the sum of priorities of items that are shared in groups of elves of
+/// the given size.  Every elf has to be in a full group.
+pub fn badge_priority(rucksacks: &[Rucksack], group_size: usize) -> Result<i32> {
+    if group_size == 0 {
+        return Err(Error::solve("groups need at least one elf"));
+    }
+
+    rucksacks.chunks(group_size).enumerate()
+        .map(|(i, group)| {
+            let line = i * group_size + 1;
+
+            if group.len() < group_size {
+                let reason = format!("the last group only has {} of {} elves", group.len(), group_size);
+                return Err(Error::parse("", reason).at_line(line));
+            }
+
+            shared_badge(group).map(priority).map_err(|err| err.at_line(line))
+        })
         .sum()
 }
 
@@ -76,11 +139,11 @@ mod tests {
 
     #[test]
     fn test_shared_halves() {
-        let rucksacks = parse_rucksacks("input/day3_sample.txt");
+        let rucksacks = Day3::parse(include_str!("../input/day3_sample.txt")).unwrap();
         let sample_shared = ['p', 'L', 'P', 'v', 't', 's'];
 
         for (rucksack, expected) in rucksacks.iter().zip(sample_shared) {
-            assert_eq!(expected, rucksack.shared_halves());
+            assert_eq!(expected, rucksack.shared_halves().unwrap());
         }
     }
 
@@ -88,19 +151,38 @@ mod tests {
     fn test_priority() {
         assert_eq!(16, priority('p'));
         assert_eq!(38, priority('L'));
+
+        for p in 1..=52 {
+            assert_eq!(p, priority(item(p)));
+        }
     }
 
     #[test]
     fn test_total_priority() {
-        let rucksacks = parse_rucksacks("input/day3_sample.txt");
+        let rucksacks = Day3::parse(include_str!("../input/day3_sample.txt")).unwrap();
+
+        assert_eq!(157, total_priority(&rucksacks).unwrap());
+
+        let err = total_priority(&parse_rucksacks("abcabc\nabcd\n").unwrap()).unwrap_err();
+        assert_eq!("1: several items (abc) are shared by both compartments: 'abcabc'", err.to_string());
 
-        assert_eq!(157, total_priority(&rucksacks));
+        // parsing only checks the format, so a part 1 mistake doesn't stop part 2.
+        let rucksacks = Day3::parse("ab\n").unwrap();
+        assert!(Day3::part1(&rucksacks).is_err());
     }
 
     #[test]
     fn test_badge_priority() {
-        let rucksacks = parse_rucksacks("input/day3_sample.txt");
+        let rucksacks = Day3::parse(include_str!("../input/day3_sample.txt")).unwrap();
 
-        assert_eq!(70, badge_priority(&rucksacks));
+        assert_eq!(70, badge_priority(&rucksacks, 3).unwrap());
+
+        // the whole sample shares no item, and pairs share more than one.
+        let err = badge_priority(&rucksacks, 6).unwrap_err();
+        assert!(err.to_string().starts_with("1: no item is shared by the group"), "{}", err);
+        assert!(badge_priority(&rucksacks, 2).is_err());
+
+        let err = badge_priority(&rucksacks[..4], 3).unwrap_err();
+        assert_eq!("4: the last group only has 1 of 3 elves", err.to_string());
     }
-}
\ No newline at end of file
+}
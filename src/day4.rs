@@ -1,66 +1,79 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::ops::RangeInclusive;
 use std::str::FromStr;
-use std::string::ParseError;
-use itertools::Itertools;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let assignments = parse_assignments("input/day4.txt");
+use crate::error::{Error, parse_lines, parse_num, Result};
+use crate::intervals::IntervalSet;
+use crate::solution::Solution;
 
-    println!("Day 4");
-    println!("Part 1: {}", num_overlap(&assignments, Assignment::full_overlap));
-    println!("Part 2: {}", num_overlap(&assignments, Assignment::partial_overlap));
-}
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Vec<Assignment>;
+    type Part1 = usize;
+    type Part2 = usize;
 
-fn parse_assignments(filename: &str) -> Vec<Assignment> {
-    let f = File::open(filename);
-    let f = BufReader::new(f.unwrap());
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_assignments(input)
+    }
 
-    f.lines().flatten()
-        .map(|line| line.parse()).flatten()
-        .collect::<Vec<Assignment>>()
+    fn part1(assignments: &Self::Input) -> Result<Self::Part1> {
+        Ok(num_overlap(assignments, Assignment::full_overlap))
+    }
+
+    fn part2(assignments: &Self::Input) -> Result<Self::Part2> {
+        Ok(num_overlap(assignments, Assignment::partial_overlap))
+    }
 }
 
-struct Assignment {
-    a: RangeInclusive<i32>,
-    b: RangeInclusive<i32>,
+/// parse_assignments parses the pairs of section assignments, one pair per line.
+pub fn parse_assignments(input: &str) -> Result<Vec<Assignment>> {
+    parse_lines(input)
+}
+
+pub struct Assignment {
+    a: IntervalSet,
+    b: IntervalSet,
 }
 
 impl FromStr for Assignment {
-    type Err = ParseError;
+    type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> Result<Self> {
         // Assignment looks like '2-4,6-8'
+        fn parse_range(range: &str) -> Result<IntervalSet> {
+            let (start, end) = range.split_once('-').ok_or_else(|| Error::parse(range, "invalid range"))?;
+            let (start, end) = (parse_num(start)?, parse_num(end)?);
+
+            // a backwards range would be an empty set, which every other range fully overlaps.
+            if start > end {
+                return Err(Error::parse(range, "range start is after its end"));
+            }
+
+            Ok(IntervalSet::from(start..=end))
+        }
 
-        let assignments = s.split(",").collect_vec();
-        let a_bounds: Vec<i32> = assignments[0].split("-").map(|s| s.parse()).flatten().collect_vec();
-        let b_bounds: Vec<i32> = assignments[1].split("-").map(|s| s.parse()).flatten().collect_vec();
+        let (a, b) = s.split_once(',').ok_or_else(|| Error::parse(s, "invalid assignment"))?;
 
         Ok(Assignment {
-            a: RangeInclusive::new(a_bounds[0], a_bounds[1]),
-            b: RangeInclusive::new(b_bounds[0], b_bounds[1]),
+            a: parse_range(a)?,
+            b: parse_range(b)?,
         })
     }
 }
 
 impl Assignment {
     /// full_overlap returns whether one of the ranges in this assignment fully contains the other.
-    fn full_overlap(&self) -> bool {
-        (self.a.contains(self.b.start()) && self.a.contains(self.b.end()))
-            || (self.b.contains(self.a.start()) && self.b.contains(self.a.end()))
+    pub fn full_overlap(&self) -> bool {
+        self.a.is_superset(&self.b) || self.b.is_superset(&self.a)
     }
 
-    /// partial_overlap returns whether one of the ranges in this assignment partially contains the other.
-    fn partial_overlap(&self) -> bool {
-        self.a.contains(self.b.start()) || self.a.contains(self.b.end())
-            || self.b.contains(self.a.start()) || self.b.contains(self.a.end())
+    /// partial_overlap returns whether the ranges in this assignment have any sections in common.
+    pub fn partial_overlap(&self) -> bool {
+        !self.a.intersection(&self.b).is_empty()
     }
 }
 
 /// num_overlap returns the number of assignments where one assignment completely covers the other.
-fn num_overlap(assignments: &Vec<Assignment>, overlap_fn: fn(&Assignment) -> bool) -> usize {
+pub fn num_overlap(assignments: &[Assignment], overlap_fn: fn(&Assignment) -> bool) -> usize {
     assignments.iter().filter(|a| overlap_fn(a)).count()
 }
 
@@ -70,15 +83,21 @@ mod tests {
 
     #[test]
     fn test_num_overlap_full() {
-        let assignments = parse_assignments("input/day4_sample.txt");
+        let assignments = Day4::parse(include_str!("../input/day4_sample.txt")).unwrap();
 
         assert_eq!(2, num_overlap(&assignments, Assignment::full_overlap));
     }
 
     #[test]
     fn test_num_overlap_partial() {
-        let assignments = parse_assignments("input/day4_sample.txt");
+        let assignments = Day4::parse(include_str!("../input/day4_sample.txt")).unwrap();
 
         assert_eq!(4, num_overlap(&assignments, Assignment::partial_overlap));
     }
+
+    #[test]
+    fn test_parse_backwards_range() {
+        let err = Day4::parse("2-4,6-8\n2-4,5-3\n").err().unwrap();
+        assert_eq!("2: range start is after its end: '5-3'", err.to_string());
+    }
 }
\ No newline at end of file
@@ -1,66 +1,86 @@
 use std::collections::VecDeque;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use std::str::FromStr;
-use std::string::ParseError;
 use itertools::Itertools;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let puzzle = parse_puzzle("input/day5.txt");
+use crate::error::{Error, parse_num, Result};
+use crate::solution::Solution;
 
-    println!("Day 5");
-    println!("Part 1: {}", puzzle.run(Instruction::run_single));
-    println!("Part 2: {}", puzzle.run(Instruction::run_stack));
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = Puzzle;
+    type Part1 = String;
+    type Part2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_puzzle(input)
+    }
+
+    fn part1(puzzle: &Self::Input) -> Result<Self::Part1> {
+        Ok(puzzle.run(Instruction::run_single))
+    }
+
+    fn part2(puzzle: &Self::Input) -> Result<Self::Part2> {
+        Ok(puzzle.run(Instruction::run_stack))
+    }
 }
 
-struct Puzzle {
+pub struct Puzzle {
     crates: Vec<VecDeque<char>>,
     instructions: Vec<Instruction>,
 }
 
-fn parse_puzzle(filename: &str) -> Puzzle {
-    let f = File::open(filename);
-    let f = BufReader::new(f.unwrap());
-
-    let mut parsing_crates = true;
+/// parse_puzzle parses the drawing of the stacks of crates, followed by a blank line and the
+/// rearrangement instructions.
+pub fn parse_puzzle(input: &str) -> Result<Puzzle> {
     let mut crate_lines = Vec::new();
+    let mut crates = None;
     let mut instructions = Vec::new();
 
-    for line in f.lines().flatten() {
-        if parsing_crates {
+    for (i, line) in input.lines().enumerate() {
+        match &crates {
             // Beginning of file is vertical ascii art of crates, followed by a newline.
-            if &line == "" {
-                parsing_crates = false;
-            } else {
-                crate_lines.push(line);
-            }
-        } else {
-            // Rest of the file is instructions
-            instructions.push(line.parse::<Instruction>().unwrap());
+            None if line.is_empty() => crates = Some(parse_crates(&crate_lines)?),
+            None => crate_lines.push(line.to_string()),
+
+            // Rest of the file is instructions, which refer to stacks by number.
+            Some(crates) => {
+                let instruction = line.parse::<Instruction>().map_err(|err| err.at_line(i + 1))?;
+
+                let stacks = 1..=crates.len();
+                if !stacks.contains(&instruction.from) || !stacks.contains(&instruction.to) {
+                    return Err(Error::parse(line, "no such stack").at_line(i + 1));
+                }
+
+                instructions.push(instruction);
+            },
         }
     }
 
-    let crates = parse_crates(&crate_lines);
+    let crates = match crates {
+        Some(crates) => crates,
+        None => parse_crates(&crate_lines)?,
+    };
 
-    Puzzle { crates, instructions }
+    Ok(Puzzle { crates, instructions })
 }
 
 /// parse_crates parses lines that look like the following, with an arbitrary number of crates:
-/// ```
+/// ```text
 ///     [D]
 /// [N] [C]
 /// [Z] [M] [P]
 ///  1   2   3
 /// ```
-fn parse_crates(lines: &Vec<String>) -> Vec<VecDeque<char>> {
+fn parse_crates(lines: &[String]) -> Result<Vec<VecDeque<char>>> {
     // Last line has crate numbers, crates may appear at the 1, 5, 9, etc. index on each line.
     // The last line doesn't have trailing whitespace or a column, so num_crates = (len + 2) / 4.
     fn num_crates(line_len: usize) -> usize {
         (line_len + 2) / 4
     }
 
-    let mut crates = vec![VecDeque::new(); num_crates(lines.last().unwrap().len())];
+    let numbers = lines.last().ok_or_else(|| Error::parse("", "missing crates"))?;
+    let mut crates = vec![VecDeque::new(); num_crates(numbers.len())];
 
     // Crates can't float over empty air, so look at them in reverse.
     // The last line has crate numbers, so skip it.  Crate contents are at index 1, +4, etc.
@@ -73,13 +93,15 @@ fn parse_crates(lines: &Vec<String>) -> Vec<VecDeque<char>> {
             .for_each(|(i, c)| crates[i].push_back(c));
     });
 
-    crates
+    Ok(crates)
 }
 
-type InstructionRun = fn(&Instruction, &mut Vec<VecDeque<char>>);
+/// InstructionRun moves crates between stacks for an instruction, like a crane would.
+pub type InstructionRun = fn(&Instruction, &mut [VecDeque<char>]);
 
 impl Puzzle {
-    fn run(&self, instruction_run: InstructionRun) -> String {
+    /// run runs every instruction with the given crane, and returns the crates on top of the stacks.
+    pub fn run(&self, instruction_run: InstructionRun) -> String {
         let mut crates = self.crates.clone();
 
         for instruction in &self.instructions {
@@ -89,56 +111,54 @@ impl Puzzle {
         // Puzzle solution is the letters in the crate at the top of each stack.
         // Some stacks may be empty - flatten to ignore them in the answer.
         crates.iter()
-            .map(|c: &VecDeque<char>| c.back()).flatten()
+            .filter_map(|c: &VecDeque<char>| c.back())
             .cloned().collect::<String>()
     }
 }
 
 #[derive(Debug)]
-struct Instruction {
+pub struct Instruction {
     num: usize,
     from: usize,
     to: usize,
 }
 
 impl FromStr for Instruction {
-    type Err = ParseError;
+    type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> Result<Self> {
         // Instructions look like 'move 3 from 1 to 3'
-        let nums = s.replace("move ", "").replace("from ", "").replace("to ", "")
-            .split_whitespace()
-            .map(|n| n.parse::<usize>())
-            .flatten()
-            .collect_vec();
-
-        return Ok(Instruction {
-            num: nums[0],
-            from: nums[1],
-            to: nums[2],
-        })
+        match s.split_whitespace().collect_vec().as_slice() {
+            ["move", num, "from", from, "to", to] => Ok(Instruction {
+                num: parse_num(num)?,
+                from: parse_num(from)?,
+                to: parse_num(to)?,
+            }),
+            _ => Err(Error::parse(s, "invalid instruction")),
+        }
     }
 }
 
 impl Instruction {
-    fn run_single(&self, crates: &mut Vec<VecDeque<char>>) {
+    /// run_single runs the instruction with a crane that moves one crate at a time.
+    pub fn run_single(&self, crates: &mut [VecDeque<char>]) {
         // Instructions look like 'move 3 from 1 to 3'
         for _ in 0..self.num {
-            if let Some(c) = crates[self.from.clone() - 1].pop_back() {
-                crates[self.to.clone() - 1].push_back(c)
+            if let Some(c) = crates[self.from - 1].pop_back() {
+                crates[self.to - 1].push_back(c)
             }
         }
     }
 
-    fn run_stack(&self, crates: &mut Vec<VecDeque<char>>) {
+    /// run_stack runs the instruction with a crane that moves all of the crates at once.
+    pub fn run_stack(&self, crates: &mut [VecDeque<char>]) {
         // Instructions look like 'move 3 from 1 to 3'
-        let stack = (0..self.num).map(|_| crates[self.from.clone() - 1].pop_back())
-            .flatten()
+        let stack = (0..self.num).filter_map(|_| crates[self.from - 1].pop_back())
             .collect_vec();
 
         // Push the stack in reverse order to preserve it's original order.
         for c in stack.into_iter().rev() {
-            crates[self.to.clone() - 1].push_back(c)
+            crates[self.to - 1].push_back(c)
         }
     }
 }
@@ -149,14 +169,14 @@ mod tests {
 
     #[test]
     fn test_puzzle_run_single() {
-        let puzzle = parse_puzzle("input/day5_sample.txt");
+        let puzzle = Day5::parse(include_str!("../input/day5_sample.txt")).unwrap();
 
         assert_eq!("CMZ", puzzle.run(Instruction::run_single));
     }
 
     #[test]
     fn test_puzzle_run_stack() {
-        let puzzle = parse_puzzle("input/day5_sample.txt");
+        let puzzle = Day5::parse(include_str!("../input/day5_sample.txt")).unwrap();
 
         assert_eq!("MCD", puzzle.run(Instruction::run_stack));
     }
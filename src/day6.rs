@@ -1,22 +1,39 @@
-use std::fs::read_to_string;
 use itertools::Itertools;
 
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+
 static PACKET: usize = 4;
 static MESSAGE: usize = 14;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let data = read_to_string("input/day6.txt").unwrap();
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = String;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.trim().to_string())
+    }
+
+    fn part1(data: &Self::Input) -> Result<Self::Part1> {
+        marker_idx(data, PACKET)
+    }
 
-    println!("Part 1: {}", marker_idx(data.trim(), PACKET));
-    println!("Part 2: {}", marker_idx(data.trim(), MESSAGE));
+    fn part2(data: &Self::Input) -> Result<Self::Part2> {
+        marker_idx(data, MESSAGE)
+    }
 }
 
-fn marker_idx(data: &str, len: usize) -> usize {
-    len + data.as_bytes()
+/// marker_idx returns the number of characters processed before the end of the first marker,
+/// which is a run of len different characters, or an error if there isn't one.
+pub fn marker_idx(data: &str, len: usize) -> Result<usize> {
+    data.as_bytes()
         .windows(len)
         .position(|packet| packet.iter().unique().count() == len)
-        .unwrap_or(0)
+        .map(|idx| idx + len)
+        .ok_or_else(|| Error::solve(format!("no run of {} different characters", len)))
 }
 
 #[cfg(test)]
@@ -25,20 +42,22 @@ mod tests {
 
     #[test]
     fn test_marker_idx_packet() {
-        assert_eq!(7, marker_idx("mjqjpqmgbljsphdztnvjfqwrcgsmlb", PACKET));
-        assert_eq!(5, marker_idx("bvwbjplbgvbhsrlpgdmjqwftvncz", PACKET));
-        assert_eq!(6, marker_idx("nppdvjthqldpwncqszvftbrmjlhg", PACKET));
-        assert_eq!(10, marker_idx("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", PACKET));
-        assert_eq!(11, marker_idx("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", PACKET));
+        assert_eq!(7, marker_idx("mjqjpqmgbljsphdztnvjfqwrcgsmlb", PACKET).unwrap());
+        assert_eq!(5, marker_idx("bvwbjplbgvbhsrlpgdmjqwftvncz", PACKET).unwrap());
+        assert_eq!(6, marker_idx("nppdvjthqldpwncqszvftbrmjlhg", PACKET).unwrap());
+        assert_eq!(10, marker_idx("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", PACKET).unwrap());
+        assert_eq!(11, marker_idx("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", PACKET).unwrap());
     }
 
     #[test]
     fn test_marker_idx_message() {
-        assert_eq!(19, marker_idx("mjqjpqmgbljsphdztnvjfqwrcgsmlb", MESSAGE));
-        assert_eq!(23, marker_idx("bvwbjplbgvbhsrlpgdmjqwftvncz", MESSAGE));
-        assert_eq!(23, marker_idx("nppdvjthqldpwncqszvftbrmjlhg", MESSAGE));
-        assert_eq!(29, marker_idx("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", MESSAGE));
-        assert_eq!(26, marker_idx("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", MESSAGE));
-
+        assert_eq!(19, marker_idx("mjqjpqmgbljsphdztnvjfqwrcgsmlb", MESSAGE).unwrap());
+        assert_eq!(23, marker_idx("bvwbjplbgvbhsrlpgdmjqwftvncz", MESSAGE).unwrap());
+        assert_eq!(23, marker_idx("nppdvjthqldpwncqszvftbrmjlhg", MESSAGE).unwrap());
+        assert_eq!(29, marker_idx("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", MESSAGE).unwrap());
+        assert_eq!(26, marker_idx("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", MESSAGE).unwrap());
+
+        assert!(marker_idx("abcabcabcabcabc", MESSAGE).is_err());
+        assert!(marker_idx("abc", PACKET).is_err());
     }
 }
\ No newline at end of file
@@ -1,24 +1,36 @@
 use std::collections::{HashMap, VecDeque};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 
-#[allow(dead_code)]
-pub fn solution() {
-    let root = parse_output("input/day7.txt");
+use crate::error::{Error, parse_num, Result};
+use crate::solution::Solution;
 
-    println!("Day 7");
-    println!("Part 1: {}", total_small(&root));
-    println!("Part 2: {}", delete_dir(&root, 70_000_000, 30_000_000));
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Dir;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_output(input)
+    }
+
+    fn part1(root: &Self::Input) -> Result<Self::Part1> {
+        Ok(total_small(root))
+    }
+
+    fn part2(root: &Self::Input) -> Result<Self::Part2> {
+        delete_dir(root, 70_000_000, 30_000_000)
+    }
 }
 
-fn parse_output(filename: &str) -> Dir {
+/// parse_output parses the terminal output of browsing the filesystem into a tree of directories.
+pub fn parse_output(input: &str) -> Result<Dir> {
     let mut path: VecDeque<String> = VecDeque::new();
     let mut root = Dir::new("/".to_string());
 
-    let f = File::open(filename);
-    let f = BufReader::new(f.unwrap());
+    for (i, line) in input.lines().enumerate() {
+        let no_such_dir = || Error::parse(line, "no such directory").at_line(i + 1);
 
-    for line in f.lines().flatten() {
         if line == "$ cd .." {
             // $ cd .. - go up one level
             path.pop_back();
@@ -29,6 +41,7 @@ fn parse_output(filename: &str) -> Dir {
 
             if dir_name != "/" {
                 path.push_back(dir_name);
+                get_dir(&mut root, &path).ok_or_else(no_such_dir)?;
             }
 
         } else if line.starts_with("$ ls") {
@@ -37,12 +50,14 @@ fn parse_output(filename: &str) -> Dir {
         } else if line.starts_with("dir") {
             // dir d - record a directory
             let dir_name = line.replace("dir ", "");
-            get_dir(&mut root, &path).children.insert(dir_name.clone(), Dir::new(dir_name.clone()));
+            get_dir(&mut root, &path).ok_or_else(no_such_dir)?
+                .children.insert(dir_name.clone(), Dir::new(dir_name.clone()));
 
         } else if !line.is_empty() {
             // 8033020 d.log - add to the total size of this dir
-            let file_size = line.split_whitespace().next().unwrap().parse::<u64>().unwrap();
-            get_dir(&mut root, &path).size += file_size;
+            let file_size = parse_num::<u64>(line.split_whitespace().next().unwrap_or(line))
+                .map_err(|err| err.at_line(i + 1))?;
+            get_dir(&mut root, &path).ok_or_else(no_such_dir)?.size += file_size;
         }
     }
 
@@ -61,21 +76,22 @@ fn parse_output(filename: &str) -> Dir {
 
     add_child_size(&mut root);
 
-    root
+    Ok(root)
 }
 
-fn get_dir<'a>(root: &'a mut Dir, path: &VecDeque<String>) -> &'a mut Dir {
+/// get_dir returns the directory at the given path, or None if it hasn't been listed.
+fn get_dir<'a>(root: &'a mut Dir, path: &VecDeque<String>) -> Option<&'a mut Dir> {
     let mut dir = root;
 
     for dir_name in path {
-        dir = dir.children.get_mut(dir_name).unwrap();
+        dir = dir.children.get_mut(dir_name)?;
     }
 
-    dir
+    Some(dir)
 }
 
 #[derive(Debug)]
-struct Dir {
+pub struct Dir {
     _name: String,
     size: u64,
     children: HashMap<String, Dir>,
@@ -83,7 +99,7 @@ struct Dir {
 
 impl Dir {
     fn new(name: String) -> Self {
-        return Dir {
+        Dir {
             _name: name,
             size: 0,
             children: HashMap::new(),
@@ -93,14 +109,14 @@ impl Dir {
 
 /// total_small returns the total size of directories less than 100_000 bytes.
 /// Directory size is recursive, so file sizes can be counted more than once.
-fn total_small(dir: &Dir) -> u64 {
+pub fn total_small(dir: &Dir) -> u64 {
     let mut total = 0;
 
     if dir.size < 100_000 {
         total += dir.size;
     }
 
-    for (_, child) in &dir.children {
+    for child in dir.children.values() {
         total += total_small(child);
     }
 
@@ -108,7 +124,8 @@ fn total_small(dir: &Dir) -> u64 {
 }
 
 /// delete_dir returns the size of the smallest directory to delete that will free enough space.
-fn delete_dir(root: &Dir, total: u64, need: u64) -> u64 {
+/// It's an error if there's already enough space, or the files don't fit on the disk.
+pub fn delete_dir(root: &Dir, total: u64, need: u64) -> Result<u64> {
     // find the size of the smallest directory that frees up enough space to reach need.
 
     fn smallest_free_dir(dir: &Dir, to_free: u64, smallest: u64) -> u64 {
@@ -118,15 +135,19 @@ fn delete_dir(root: &Dir, total: u64, need: u64) -> u64 {
             smallest = dir.size;
         }
 
-        for (_, child_dir) in &dir.children {
+        for child_dir in dir.children.values() {
             smallest = smallest_free_dir(child_dir, to_free, smallest);
         }
 
         smallest
     }
 
-    let to_free = need - (total - root.size);
-    smallest_free_dir(root, to_free, u64::MAX)
+    let free = total.checked_sub(root.size)
+        .ok_or_else(|| Error::solve(format!("the files ({}) are too big for the disk", root.size)))?;
+    let to_free = need.checked_sub(free)
+        .ok_or_else(|| Error::solve(format!("there's already enough free space ({})", free)))?;
+
+    Ok(smallest_free_dir(root, to_free, u64::MAX))
 }
 
 #[cfg(test)]
@@ -135,15 +156,16 @@ mod tests {
 
     #[test]
     fn test_total_small() {
-        let root = parse_output("input/day7_sample.txt");
+        let root = Day7::parse(include_str!("../input/day7_sample.txt")).unwrap();
 
         assert_eq!(95437, total_small(&root));
     }
 
     #[test]
     fn test_delete_dir() {
-        let root = parse_output("input/day7_sample.txt");
+        let root = Day7::parse(include_str!("../input/day7_sample.txt")).unwrap();
 
-        assert_eq!(24933642, delete_dir(&root, 70_000_000, 30_000_000));
+        assert_eq!(24933642, delete_dir(&root, 70_000_000, 30_000_000).unwrap());
+        assert!(delete_dir(&root, 100_000_000, 30_000_000).is_err());
     }
 }
@@ -1,102 +1,53 @@
-use std::collections::HashSet;
-use std::fmt::{Debug, Formatter};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
 use itertools::Itertools;
 use rayon::prelude::*;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let trees = load_trees("input/day8.txt");
-
-    println!("Day 8");
-    println!("Part 1: {}", count_visible(&trees));
-    println!("Part 2: {}", highest_score(&trees));
-}
+use crate::error::{Error, Result};
+use crate::grid::{Grid, Point};
+use crate::solution::Solution;
 
-type Trees = Vec<Vec<u32>>;
+pub struct Day8;
 
-#[derive(Eq, PartialEq, Hash, Copy, Clone)]
-struct Point { row: usize, col: usize}
+impl Solution for Day8 {
+    type Input = Trees;
+    type Part1 = usize;
+    type Part2 = usize;
 
-impl Point {
-    fn at(row: usize, col: usize) -> Self {
-        Point { row, col }
+    fn parse(input: &str) -> Result<Self::Input> {
+        load_trees(input)
     }
-}
 
-impl Debug for Point {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.row, self.col)
+    fn part1(trees: &Self::Input) -> Result<Self::Part1> {
+        Ok(count_visible(trees))
     }
-}
 
-fn tree_at(trees: &Trees, point: Point) -> u32 {
-    trees[point.row][point.col]
+    fn part2(trees: &Self::Input) -> Result<Self::Part2> {
+        Ok(highest_score(trees))
+    }
 }
 
-fn load_trees(filename: &str) -> Trees {
-    let f = File::open(filename);
-    let f = BufReader::new(f.unwrap());
+pub type Trees = Grid<u32>;
 
-    f.lines().flatten()
-        .map(|line| line.chars().map(|c| c.to_digit(10)).flatten().collect_vec())
-        .collect_vec()
+/// load_trees parses a grid of tree heights, where each tree is a single digit.
+/// Every row of the forest must be the same width.
+pub fn load_trees(input: &str) -> Result<Trees> {
+    Grid::parse(input, |c| c.to_digit(10).ok_or_else(|| Error::parse(&c.to_string(), "tree heights must be digits")))
 }
 
 /// count_visible returns the total number of trees that are visible from the sides of the forest.
-fn count_visible(trees: &Trees) -> usize {
-    let mut visible: HashSet<Point> = HashSet::new();
-
-    let (height, width) = (trees.len(), trees[0].len());
-
-    for row in 0..height {
-        for col in 0..width {
-            let right = (0..width).map(|c| Point::at(row, c)).collect_vec();
-            let left = (0..width).rev().map(|c| Point::at(row, c)).collect_vec();
-            let down = (0..height).map(|r| Point::at(r, col)).collect_vec();
-            let up = (0..height).rev().map(|r| Point::at(r, col)).collect_vec();
-
-            for order in [right, left, down, up] {
-                let mut tallest =  -1;
-                for point in order {
-                    let height = tree_at(trees, point) as i32;
-                    if height > tallest {
-                        visible.insert(point);
-                        tallest = height;
-                    }
-                }
-            }
-        }
-    }
-
-    visible.len()
-}
-
-#[allow(dead_code)]
-fn print_visible(trees: &Trees, visible: &HashSet<Point>) {
-    for row in 0..trees.len() {
-        for col in 0..trees[row].len() {
-            let point = Point::at(row, col);
-
-            if visible.contains(&point) {
-                print!("#")
-            } else {
-                print!(" ")
-            }
-        }
-
-        println!()
-    }
+/// A tree is visible if every tree between it and the edge in any direction is shorter.
+pub fn count_visible(trees: &Trees) -> usize {
+    trees.points()
+        .filter(|&point| {
+            Point::ORTHOGONAL.iter()
+                .any(|&dir| trees.ray(point, dir).all(|other| trees.get(other) < trees.get(point)))
+        })
+        .count()
 }
 
 /// highest_score returns the highest tree score out of all the trees, where a tree score is the
 /// product of the distance from a tree to a tree with it's height or higher in all directions.
-fn highest_score(trees: &Trees) -> usize {
-    let points = (0..trees.len()).cartesian_product(0..trees[0].len())
-        .map(|(row, col)| Point::at(row, col))
-        .collect_vec();
+pub fn highest_score(trees: &Trees) -> usize {
+    let points = trees.points().collect_vec();
 
     points.par_iter()
         .map(|&point| tree_score(trees, point))
@@ -106,24 +57,19 @@ fn highest_score(trees: &Trees) -> usize {
 
 /// tree_score returns the score for the given tree.  A tree's score is the product of the
 /// number of trees visible from a location that are shorter than the tree.
-fn tree_score(trees: &Trees, at: Point) -> usize {
-    let (height, width) = (trees.len(), trees[0].len());
-
-    let right = (at.col..width).map(|c| Point::at(at.row, c)).collect_vec();
-    let left = (0..=at.col).rev().map(|c| Point::at(at.row, c)).collect_vec();
-    let down = (0..=at.row).rev().map(|r| Point::at(r, at.col)).collect_vec();
-    let up= (at.row..height).map(|r| Point::at(r, at.col)).collect_vec();
-
-    let tree_height = tree_at(trees, at);
+pub fn tree_score(trees: &Trees, at: Point) -> usize {
+    let Some(tree_height) = trees.get(at) else {
+        return 0;
+    };
 
     let mut score = 1;
 
-    for order in [up, left, right, down] {
+    for dir in Point::ORTHOGONAL {
         let mut dir_score = 0;
-        for point in order.into_iter().skip(1) {
+        for point in trees.ray(at, dir) {
             dir_score += 1;
 
-            if tree_height <= tree_at(trees, point) {
+            if trees.get(point).is_some_and(|height| tree_height <= height) {
                 break;
             }
         }
@@ -140,14 +86,14 @@ mod tests {
 
     #[test]
     fn test_count_visible() {
-        let trees = load_trees("input/day8_sample.txt");
+        let trees = Day8::parse(include_str!("../input/day8_sample.txt")).unwrap();
 
         assert_eq!(21, count_visible(&trees));
     }
 
     #[test]
     fn test_highest_score() {
-        let trees = load_trees("input/day8_sample.txt");
+        let trees = Day8::parse(include_str!("../input/day8_sample.txt")).unwrap();
 
         assert_eq!(8, highest_score(&trees));
     }
@@ -1,64 +1,61 @@
 use std::collections::HashSet;
 use std::fmt::{Debug, Formatter};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use std::str::FromStr;
-use std::string::ParseError;
-use itertools::Itertools;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let moves = parse_moves("input/day9.txt");
+use crate::error::{Error, parse_lines, parse_num, Result};
+use crate::solution::Solution;
 
-    println!("Day 9");
-    println!("Part 1: {}", count_visited(&moves));
-    println!("Part 2: {}", count_visited_long(&moves));
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input = Vec<Move>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_moves(input)
+    }
+
+    fn part1(moves: &Self::Input) -> Result<Self::Part1> {
+        Ok(count_visited(moves))
+    }
+
+    fn part2(moves: &Self::Input) -> Result<Self::Part2> {
+        Ok(count_visited_long(moves))
+    }
 }
 
 enum Direction {
     Left, Right, Up, Down,
 }
 
-struct Move {
+pub struct Move {
     dir: Direction,
     amount: usize,
 }
 
 impl FromStr for Move {
-    type Err = ParseError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.starts_with("L") {
-            Ok(Move {
-                dir: Direction::Left,
-                amount: s.replace("L ", "").parse().unwrap()
-            })
-        } else if s.starts_with("R") {
-            Ok(Move {
-                dir: Direction::Right,
-                amount: s.replace("R ", "").parse().unwrap()
-            })
-        } else if s.starts_with("U") {
-            Ok(Move {
-                dir: Direction::Up,
-                amount: s.replace("U ", "").parse().unwrap()
-            })
-        } else if s.starts_with("D") {
-            Ok(Move {
-                dir: Direction::Down,
-                amount: s.replace("D ", "").parse().unwrap()
-            })
-        } else {
-            panic!("Invalid direction: '{}'", s)
-        }
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        // Moves look like 'R 4'
+        let (dir, amount) = s.split_once(' ').ok_or_else(|| Error::parse(s, "invalid move"))?;
+
+        let dir = match dir {
+            "L" => Direction::Left,
+            "R" => Direction::Right,
+            "U" => Direction::Up,
+            "D" => Direction::Down,
+            _ => return Err(Error::parse(s, "invalid direction")),
+        };
+
+        Ok(Move { dir, amount: parse_num(amount)? })
     }
 }
 
-fn parse_moves(filename: &str) -> Vec<Move> {
-    let f = File::open(filename);
-    let f = BufReader::new(f.unwrap());
-
-    f.lines().flatten().flat_map(|line| line.parse()).collect_vec()
+/// parse_moves parses the moves of the head of the rope, one per line.
+pub fn parse_moves(input: &str) -> Result<Vec<Move>> {
+    parse_lines(input)
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash)]
@@ -96,7 +93,7 @@ impl Position {
 }
 
 /// count_visited returns the number of positions that the tail visited with a rope with 1 knot.
-fn count_visited(moves: &Vec<Move>) -> usize {
+pub fn count_visited(moves: &[Move]) -> usize {
     let mut tail_positions: HashSet<Position> = HashSet::new();
     let mut head = Position::at(0, 0);
     let mut tail = Position::at(0, 0);
@@ -116,9 +113,9 @@ fn count_visited(moves: &Vec<Move>) -> usize {
 
 /// count_visited_long returns the number of positions that the tail visited with a rope with
 /// 10 knots.
-fn count_visited_long(moves: &Vec<Move>) -> usize {
+pub fn count_visited_long(moves: &[Move]) -> usize {
     let mut tail_positions: HashSet<Position> = HashSet::new();
-    let mut rope = vec![Position::at(0, 0); 10];
+    let mut rope = [Position::at(0, 0); 10];
 
     tail_positions.insert(rope[9]);
 
@@ -126,7 +123,7 @@ fn count_visited_long(moves: &Vec<Move>) -> usize {
         for _ in 0..m.amount {
             rope[0].step(&m.dir);
             for i in 1..rope.len() {
-                let previous = rope[i - 1].clone();
+                let previous = rope[i - 1];
 
                 rope[i].follow(&previous);
             }
@@ -193,16 +190,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_moves_invalid() {
+        let err = parse_moves("R 4\nU 4\nX 3").err().unwrap();
+        assert_eq!("3: invalid direction: 'X 3'", err.to_string());
+
+        let err = parse_moves("R 4\nU four").err().unwrap();
+        assert_eq!("2: invalid number: 'four'", err.to_string());
+    }
+
     #[test]
     fn test_count_visited() {
-        let moves = parse_moves("input/day9_sample.txt");
+        let moves = Day9::parse(include_str!("../input/day9_sample.txt")).unwrap();
 
         assert_eq!(13, count_visited(&moves));
     }
 
     #[test]
     fn test_count_visited_long() {
-        let moves = parse_moves("input/day9_sample2.txt");
+        let moves = Day9::parse(include_str!("../input/day9_sample2.txt")).unwrap();
 
         assert_eq!(36, count_visited_long(&moves));
     }
@@ -0,0 +1,378 @@
+use std::collections::HashSet;
+use std::fmt::Debug;
+
+use itertools::Itertools;
+use num_bigint::BigUint;
+
+use crate::*;
+use crate::generate::Rng;
+use crate::intervals::IntervalSet;
+
+/// CASES is the number of generated inputs each property is checked against.
+const CASES: u64 = 100;
+
+/// counterexample checks that a property holds for inputs made by generate from a range of
+/// seeds.  It returns the smallest failing input it can find, or None if they all pass.  A
+/// failing input is shrunk by trying the smaller inputs that shrink suggests, in order, and
+/// keeping the first that still fails, until none of them do.
+fn counterexample<T: Clone>(
+    cases: u64,
+    generate: impl Fn(&mut Rng) -> T,
+    shrink: impl Fn(&T) -> Vec<T>,
+    holds: impl Fn(&T) -> bool,
+) -> Option<T> {
+    let mut failing = (0..cases).map(|seed| generate(&mut Rng::new(seed))).find(|input| !holds(input))?;
+
+    while let Some(smaller) = shrink(&failing).into_iter().find(|input| !holds(input)) {
+        failing = smaller;
+    }
+
+    Some(failing)
+}
+
+/// check panics with the smallest input it can find that fails the property.
+fn check<T: Clone + Debug>(
+    cases: u64,
+    generate: impl Fn(&mut Rng) -> T,
+    shrink: impl Fn(&T) -> Vec<T>,
+    holds: impl Fn(&T) -> bool,
+) {
+    if let Some(input) = counterexample(cases, generate, shrink, holds) {
+        panic!("property doesn't hold for {:?}", input);
+    }
+}
+
+/// shrink_num returns smaller numbers to try in place of num, closest to zero first.
+fn shrink_num(num: i64) -> Vec<i64> {
+    [0, num / 2, num - num.signum()].into_iter()
+        .filter(|&smaller| smaller != num)
+        .unique()
+        .collect()
+}
+
+/// shrink_vec returns smaller lists to try in place of items: first without each item, then
+/// with each item shrunk.
+fn shrink_vec<T: Clone>(items: &[T], shrink_item: impl Fn(&T) -> Vec<T>) -> Vec<Vec<T>> {
+    let removed = (0..items.len()).map(|i| [&items[..i], &items[i + 1..]].concat());
+
+    let shrunk = (0..items.len()).flat_map(|i| {
+        shrink_item(&items[i]).into_iter().map(move |item| {
+            let mut items = items.to_vec();
+            items[i] = item;
+            items
+        })
+    }).collect_vec();
+
+    removed.chain(shrunk).collect()
+}
+
+#[test]
+fn test_counterexample() {
+    // no list has a number over 50, shrunk to the smallest list that does.
+    let generate = |rng: &mut Rng| (0..rng.below(10)).map(|_| rng.range(0..=100)).collect_vec();
+    let shrink = |items: &Vec<i64>| shrink_vec(items, |&num| shrink_num(num));
+
+    assert_eq!(Some(vec![51]), counterexample(CASES, generate, shrink, |items| items.iter().all(|&num| num <= 50)));
+    assert_eq!(None, counterexample(CASES, generate, shrink, |items| items.iter().all(|&num| num <= 100)));
+}
+
+/// Day 15's no_beacon merges each sensor's coverage of the row into ranges.  The reference
+/// checks every square on the row against every sensor.
+#[test]
+fn test_no_beacon() {
+    type Sensors = (i64, Vec<[i64; 4]>);
+
+    let generate = |rng: &mut Rng| -> Sensors {
+        let sensors = (0..rng.below(6)).map(|_| [(); 4].map(|_| rng.range(-20..=20))).collect();
+        (rng.range(-20..=20), sensors)
+    };
+
+    let shrink = |(row, sensors): &Sensors| {
+        let rows = shrink_num(*row).into_iter().map(|row| (row, sensors.clone()));
+        let sensors = shrink_vec(sensors, |sensor| {
+            (0..4).flat_map(|i| shrink_num(sensor[i]).into_iter().map(move |num| {
+                let mut sensor = *sensor;
+                sensor[i] = num;
+                sensor
+            })).collect()
+        }).into_iter().map(|sensors| (*row, sensors));
+
+        rows.chain(sensors).collect_vec()
+    };
+
+    let naive = |row: i64, sensors: &[[i64; 4]]| {
+        let dist = |[sx, sy, bx, by]: [i64; 4]| (sx - bx).abs() + (sy - by).abs();
+        let beacons = sensors.iter().map(|&[_, _, bx, by]| (bx, by)).collect::<HashSet<(i64, i64)>>();
+
+        (-100..=100)
+            .filter(|&x| !beacons.contains(&(x, row)))
+            .filter(|&x| sensors.iter().any(|&sensor| (x - sensor[0]).abs() + (row - sensor[1]).abs() <= dist(sensor)))
+            .count() as u128
+    };
+
+    check(CASES, generate, shrink, |(row, sensors)| {
+        let input = sensors.iter()
+            .map(|[sx, sy, bx, by]| format!("Sensor at x={}, y={}: closest beacon is at x={}, y={}\n", sx, sy, bx, by))
+            .collect::<String>();
+
+        day15::no_beacon(&day15::load_sensors(&input).unwrap(), *row) == naive(*row, sensors)
+    });
+}
+
+/// Monkey is a day 11 monkey, for generating specs and following them with big integers.
+#[derive(Debug, Clone)]
+struct Monkey {
+    items: Vec<i64>,
+    operation: (char, Option<i64>),
+    test: i64,
+    throws: (usize, usize),
+}
+
+/// Day 11's round keeps worry levels small by taking them modulo the product of every
+/// monkey's test, unless there's relief.  The reference never reduces them, using big integers
+/// instead, so with relief day 11 has to fail exactly when a worry level doesn't fit in a u64.
+#[test]
+fn test_round() {
+    type Monkeys = (Vec<Monkey>, i64, usize);
+
+    let generate = |rng: &mut Rng| -> Monkeys {
+        let num_monkeys = rng.below(4) + 2;
+        let monkeys = (0..num_monkeys).map(|monkey| {
+            let other = |rng: &mut Rng| (monkey + 1 + rng.below(num_monkeys - 1)) % num_monkeys;
+
+            Monkey {
+                items: (0..rng.below(4) + 1).map(|_| rng.range(1..=99)).collect(),
+                // only the first monkey might use old as its operand, like the real puzzle, so
+                // the reference's worry scores don't square more than once a round.
+                operation: (rng.choose(&['+', '*']), (monkey > 0 || rng.one_in(2)).then(|| rng.range(1..=19))),
+                test: rng.choose(&[2, 3, 5, 7, 11, 13, 17, 19, 23]),
+                throws: (other(rng), other(rng)),
+            }
+        }).collect();
+
+        (monkeys, rng.choose(&[1, 3]), rng.below(10) + 1)
+    };
+
+    let shrink = |(monkeys, reduce, rounds): &Monkeys| {
+        let fewer_rounds = shrink_num(*rounds as i64).into_iter()
+            .filter(|&rounds| rounds > 0)
+            .map(|rounds| (monkeys.clone(), *reduce, rounds as usize));
+
+        let simpler = (0..monkeys.len()).flat_map(|i| {
+            let monkey = &monkeys[i];
+            let items = shrink_vec(&monkey.items, |&item| shrink_num(item)).into_iter()
+                .filter(|items| !items.is_empty())
+                .map(|items| Monkey { items, ..monkey.clone() });
+            let operations = monkey.operation.1.into_iter()
+                .flat_map(shrink_num)
+                .map(|num| Monkey { operation: (monkey.operation.0, Some(num)), ..monkey.clone() });
+
+            items.chain(operations).map(move |monkey| {
+                let mut monkeys = monkeys.clone();
+                monkeys[i] = monkey;
+                (monkeys, *reduce, *rounds)
+            }).collect_vec()
+        });
+
+        fewer_rounds.chain(simpler).collect_vec()
+    };
+
+    // naive returns the monkey business, and the biggest worry score along the way.
+    let naive = |monkeys: &[Monkey], reduce: i64, rounds: usize| {
+        let mut biggest = BigUint::from(0u64);
+        let mut holding = monkeys.iter()
+            .map(|monkey| monkey.items.iter().map(|&item| BigUint::from(item as u64)).collect_vec())
+            .collect_vec();
+        let mut inspected = vec![0; monkeys.len()];
+
+        for _ in 0..rounds {
+            for (i, monkey) in monkeys.iter().enumerate() {
+                for old in std::mem::take(&mut holding[i]) {
+                    let operand = monkey.operation.1.map_or(old.clone(), |num| BigUint::from(num as u64));
+                    let worry = match monkey.operation.0 {
+                        '+' => &old + operand,
+                        _ => &old * operand,
+                    };
+                    biggest = biggest.max(worry.clone());
+
+                    let new = worry / BigUint::from(reduce as u64);
+
+                    let to = if (&new % BigUint::from(monkey.test as u64)) == BigUint::from(0u64) { monkey.throws.0 } else { monkey.throws.1 };
+                    holding[to].push(new);
+                    inspected[i] += 1;
+                }
+            }
+        }
+
+        (inspected.into_iter().sorted().rev().take(2).product::<u64>(), biggest)
+    };
+
+    check(CASES, generate, shrink, |(monkeys, reduce, rounds)| {
+        let input = monkeys.iter().enumerate().map(|(i, monkey)| {
+            format!(
+                "Monkey {}:\n  Starting items: {}\n  Operation: new = old {} {}\n  Test: divisible by {}\n    If true: throw to monkey {}\n    If false: throw to monkey {}\n",
+                i,
+                monkey.items.iter().join(", "),
+                monkey.operation.0,
+                monkey.operation.1.map_or("old".to_string(), |num| num.to_string()),
+                monkey.test,
+                monkey.throws.0,
+                monkey.throws.1,
+            )
+        }).join("\n");
+
+        let (business, biggest) = naive(monkeys, *reduce, *rounds);
+        let overflows = *reduce != 1 && biggest > BigUint::from(u64::MAX);
+
+        let (configs, states) = day11::parse_monkeys(&input).unwrap();
+        match day11::monkey_business(&configs, &states, *reduce as u64, *rounds) {
+            Ok(answer) => !overflows && answer == business,
+            Err(_) => overflows,
+        }
+    });
+}
+
+/// Day 25's to_snafu carries with (num + 2) % 5.  The reference picks each digit from the top
+/// down, choosing whichever leaves the least left over.  SNAFU numbers in the puzzle are
+/// positive, so only positive numbers are checked.
+#[test]
+fn test_to_snafu() {
+    let naive = |num: i64| {
+        let mut place = 1;
+        let mut largest = 2;
+        while largest < num {
+            place *= 5;
+            largest += 2 * place;
+        }
+
+        let mut left = num;
+        let mut snafu = String::new();
+
+        while place > 0 {
+            let (digit, c) = [(2, '2'), (1, '1'), (0, '0'), (-1, '-'), (-2, '=')].into_iter()
+                .min_by_key(|(digit, _)| (left - digit * place).abs())
+                .unwrap();
+
+            left -= digit * place;
+            snafu.push(c);
+            place /= 5;
+        }
+
+        snafu
+    };
+
+    let generate = |rng: &mut Rng| {
+        let digits = rng.range(1..=15) as u32;
+        rng.range(1..=10_i64.pow(digits))
+    };
+    let shrink = |&num: &i64| shrink_num(num).into_iter().filter(|&num| num > 0).collect_vec();
+
+    check(CASES * 10, generate, shrink, |&num| {
+        let snafu = day25::to_snafu(num);
+        snafu == naive(num) && day25::from_snafu(&snafu) == num
+    });
+}
+
+/// Day 17's height_after_rocks skips ahead once the rocks, wind and surface of the tower
+/// repeat.  The reference drops every rock.
+#[test]
+fn test_height_after_rocks() {
+    type Rocks = (Vec<bool>, i64);
+
+    let generate = |rng: &mut Rng| -> Rocks {
+        let jets = (0..rng.below(40) + 1).map(|_| rng.one_in(2)).collect();
+        (jets, rng.range(0..=2000))
+    };
+
+    let shrink = |(jets, rocks): &Rocks| {
+        let fewer_rocks = shrink_num(*rocks).into_iter().map(|rocks| (jets.clone(), rocks));
+        let fewer_jets = shrink_vec(jets, |_| vec![]).into_iter()
+            .filter(|jets| !jets.is_empty())
+            .map(|jets| (jets, *rocks));
+
+        fewer_rocks.chain(fewer_jets).collect_vec()
+    };
+
+    check(CASES / 5, generate, shrink, |(jets, rocks)| {
+        let input = jets.iter().map(|&left| if left { '<' } else { '>' }).collect::<String>();
+        let directions = day17::load_directions(&input).unwrap();
+
+        let order = day17::Rock::order();
+        let mut board = day17::Board::new();
+        let mut wind = 0;
+
+        for rock in 0..*rocks as usize {
+            wind = board.drop(order[rock % order.len()], &directions, wind);
+        }
+
+        day17::height_after_rocks(&directions, *rocks as usize) == board.len()
+    });
+}
+
+/// IntervalSet keeps its ranges merged and sorted as they're inserted and removed.  The
+/// reference is a bitset of the numbers 0 to 63.
+#[test]
+fn test_interval_set() {
+    // ops insert or remove ranges, some of them empty, in turn.
+    type Ops = Vec<(bool, i64, i64)>;
+
+    let generate = |rng: &mut Rng| -> (Ops, Ops) {
+        let mut ops = || (0..rng.below(8)).map(|_| (!rng.one_in(3), rng.range(0..=63), rng.range(0..=63))).collect_vec();
+        (ops(), ops())
+    };
+
+    let shrink_ops = |ops: &Ops| shrink_vec(ops, |&(insert, start, end)| {
+        let starts = shrink_num(start).into_iter().map(move |start| (insert, start, end));
+        let ends = shrink_num(end).into_iter().map(move |end| (insert, start, end));
+        starts.chain(ends).collect()
+    });
+
+    let shrink = |(a, b): &(Ops, Ops)| {
+        let a_shrunk = shrink_ops(a).into_iter().map(|a| (a, b.clone()));
+        let b_shrunk = shrink_ops(b).into_iter().map(|b| (a.clone(), b));
+        a_shrunk.chain(b_shrunk).collect_vec()
+    };
+
+    let bits = |start: i64, end: i64| (start..=end).fold(0_u64, |bits, num| bits | 1 << num);
+
+    let build = |ops: &Ops| {
+        let mut set = IntervalSet::new();
+        let mut naive = 0;
+
+        for &(insert, start, end) in ops {
+            if insert {
+                set.insert(start..=end);
+                naive |= bits(start, end);
+            } else {
+                set.remove(start..=end);
+                naive &= !bits(start, end);
+            }
+        }
+
+        (set, naive)
+    };
+
+    // matches checks the set holds the same numbers as the bitset, in normalized ranges.
+    let matches = |set: &IntervalSet, naive: u64| {
+        let ranges = set.iter().collect_vec();
+        let normalized = ranges.iter().all(|range| !range.is_empty())
+            && ranges.iter().tuple_windows().all(|(a, b)| a.end() + 1 < *b.start());
+
+        normalized
+            && ranges.iter().fold(0, |all, range| all | bits(*range.start(), *range.end())) == naive
+            && set.len() == naive.count_ones() as u128
+            && (-1..=64).all(|num| set.contains(num) == ((0..64).contains(&num) && naive & 1 << num != 0))
+    };
+
+    check(CASES * 5, generate, shrink, |(a, b)| {
+        let ((a_set, a_naive), (b_set, b_naive)) = (build(a), build(b));
+
+        matches(&a_set, a_naive)
+            && matches(&b_set, b_naive)
+            && matches(&a_set.union(&b_set), a_naive | b_naive)
+            && matches(&a_set.intersection(&b_set), a_naive & b_naive)
+            && matches(&a_set.difference(&b_set), a_naive & !b_naive)
+            && a_set.is_superset(&b_set) == (b_naive & !a_naive == 0)
+            && b.iter().all(|&(_, start, end)| a_set.contains_range(&(start..=end)) == (bits(start, end) & !a_naive == 0))
+    });
+}
@@ -0,0 +1,166 @@
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::str::FromStr;
+
+/// Result is the result of loading, parsing or solving puzzle input.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Error is an error loading, parsing or solving puzzle input.
+#[derive(Debug)]
+pub enum Error {
+    /// Io is an error reading input from a file or reader.
+    Io { file: Option<String>, err: io::Error },
+
+    /// Parse is input that doesn't match the puzzle's format.  FromStr impls only see the text
+    /// they're parsing, so the line and file are filled in as the error is returned up
+    /// through the loaders.
+    Parse { file: Option<String>, line: Option<usize>, text: String, reason: String },
+
+    /// Solve is input that parsed, but that doesn't have an answer, like a map without a path
+    /// or an answer too big to store.  There's no one piece of text to blame.
+    Solve { file: Option<String>, reason: String },
+
+    /// Fetch is a puzzle input that couldn't be downloaded from the given URL.
+    Fetch { url: String, reason: String },
+
+    /// Panic is a solution that panicked instead of answering, like one that hasn't been
+    /// finished yet.
+    Panic { message: String },
+}
+
+impl Error {
+    /// parse returns an error for text that couldn't be parsed, and the reason why.
+    pub fn parse(text: &str, reason: impl Into<String>) -> Self {
+        Error::Parse { file: None, line: None, text: text.to_string(), reason: reason.into() }
+    }
+
+    /// solve returns an error for input that parsed but doesn't have an answer, and the reason
+    /// why.
+    pub fn solve(reason: impl Into<String>) -> Self {
+        Error::Solve { file: None, reason: reason.into() }
+    }
+
+    /// at_line records the line number the error happened on, counting from 1.
+    /// The innermost line number wins if the error already has one.
+    pub fn at_line(self, line: usize) -> Self {
+        match self {
+            Error::Parse { file, line: None, text, reason } => {
+                Error::Parse { file, line: Some(line), text, reason }
+            },
+            err => err,
+        }
+    }
+
+    /// in_file records the file that contained the input that couldn't be read, parsed or solved.
+    pub fn in_file(self, filename: &str) -> Self {
+        match self {
+            Error::Io { file: None, err } => Error::Io { file: Some(filename.to_string()), err },
+            Error::Parse { file: None, line, text, reason } => {
+                Error::Parse { file: Some(filename.to_string()), line, text, reason }
+            },
+            Error::Solve { file: None, reason } => Error::Solve { file: Some(filename.to_string()), reason },
+            err => err,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io { file: Some(file), err } => write!(f, "{}: {}", file, err),
+            Error::Io { file: None, err } => write!(f, "{}", err),
+            Error::Solve { file: Some(file), reason } => write!(f, "{}: {}", file, reason),
+            Error::Solve { file: None, reason } => write!(f, "{}", reason),
+            Error::Fetch { url, reason } => write!(f, "{}: {}", url, reason),
+            Error::Panic { message } => write!(f, "panicked: {}", message),
+            Error::Parse { file, line, text, reason } => {
+                if let Some(file) = file {
+                    write!(f, "{}:", file)?;
+                }
+
+                if let Some(line) = line {
+                    write!(f, "{}:", line)?;
+                }
+
+                if file.is_some() || line.is_some() {
+                    write!(f, " ")?;
+                }
+
+                // Missing input doesn't have any text to show.
+                if text.is_empty() {
+                    write!(f, "{}", reason)
+                } else {
+                    write!(f, "{}: '{}'", reason, text)
+                }
+            },
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io { file: None, err }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { err, .. } => Some(err),
+            Error::Parse { .. } | Error::Solve { .. } | Error::Fetch { .. } | Error::Panic { .. } => None,
+        }
+    }
+}
+
+/// parse_num parses a number, returning an error that includes the text if it isn't valid.
+pub fn parse_num<T: FromStr>(text: &str) -> Result<T> {
+    text.parse().map_err(|_| Error::parse(text, "invalid number"))
+}
+
+/// parse_lines parses each line of the input, recording the line number of the first line
+/// that can't be parsed.
+pub fn parse_lines<T: FromStr<Err = Error>>(input: &str) -> Result<Vec<T>> {
+    input.lines().enumerate()
+        .map(|(i, line)| line.parse().map_err(|err: Error| err.at_line(i + 1)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let err = Error::parse("X 4", "invalid direction");
+        assert_eq!("invalid direction: 'X 4'", err.to_string());
+
+        let err = err.at_line(3).at_line(7);
+        assert_eq!("3: invalid direction: 'X 4'", err.to_string());
+
+        let err = err.in_file("input/day9.txt");
+        assert_eq!("input/day9.txt:3: invalid direction: 'X 4'", err.to_string());
+
+        let err = Error::solve("no path from S to E").at_line(2).in_file("input/day12.txt");
+        assert_eq!("input/day12.txt: no path from S to E", err.to_string());
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let nums: Vec<Num> = parse_lines("1\n2\n3").unwrap();
+        assert_eq!(vec![1, 2, 3], nums.iter().map(|num| num.0).collect::<Vec<i32>>());
+
+        let err = parse_lines::<Num>("1\ntwo\n3").unwrap_err();
+        assert_eq!("2: invalid number: 'two'", err.to_string());
+    }
+
+    #[derive(Debug)]
+    struct Num(i32);
+
+    impl FromStr for Num {
+        type Err = Error;
+
+        fn from_str(s: &str) -> Result<Self> {
+            parse_num(s).map(Num)
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+use crate::error::{Error, Result};
+use crate::scaffold::{self, read, write};
+
+/// Page is what's taken from a saved puzzle page.  There's a description for each part that's
+/// been unlocked, so the second part is only there once the first has been answered.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Page {
+    /// samples are the example blocks from every part, in order.  The sample input is the
+    /// first one.
+    pub samples: Vec<String>,
+
+    /// answers are the expected answers to the sample for each part, if one could be found.
+    pub answers: Vec<Option<String>>,
+}
+
+/// parse_page parses a puzzle page saved from adventofcode.com.  Each part's description is an
+/// `<article>`, with its examples in `<pre><code>` blocks.  The answer to the example is taken
+/// to be the last emphasized code in the description, like `<code><em>24000</em></code>`, since
+/// the puzzle ends by explaining it.  The question itself is emphasized too, but isn't code.
+pub fn parse_page(html: &str) -> Result<Page> {
+    let articles = Regex::new(r"(?s)<article[^>]*>(.*?)</article>").unwrap();
+    let samples = Regex::new(r"(?s)<pre><code>(.*?)</code></pre>").unwrap();
+    let answers = Regex::new(r"(?s)<code><em>(.*?)</em></code>|<em><code>(.*?)</code></em>").unwrap();
+
+    let mut page = Page { samples: Vec::new(), answers: Vec::new() };
+
+    for article in articles.captures_iter(html) {
+        let article = &article[1];
+
+        page.samples.extend(samples.captures_iter(article).map(|sample| text(&sample[1])));
+        page.answers.push(answers.captures_iter(article).last()
+            .and_then(|answer| answer.get(1).or_else(|| answer.get(2)))
+            .map(|answer| text(answer.as_str())));
+    }
+
+    if page.answers.is_empty() {
+        return Err(Error::solve("no puzzle descriptions found - is this a saved puzzle page?"));
+    }
+
+    if page.samples.is_empty() {
+        return Err(Error::solve("no example blocks found in the puzzle descriptions"));
+    }
+
+    Ok(page)
+}
+
+/// text returns the text of some HTML, without its tags and with entities decoded.
+fn text(html: &str) -> String {
+    let tags = Regex::new(r"<[^>]*>").unwrap();
+
+    tags.replace_all(html, "")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Extracted is what extract wrote.  If the day's module already had its own tests, the tests
+/// for the sample are left in stub for adding by hand instead.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Extracted {
+    pub files: Vec<PathBuf>,
+    pub stub: Option<String>,
+}
+
+/// extract writes the sample input from a saved puzzle page to the day's sample file in root,
+/// and fills in the expected answers in the tests new started the day's module with.  A sample
+/// file that isn't empty is never overwritten.
+pub fn extract(root: &Path, day: u8, html: &str) -> Result<Extracted> {
+    let page = parse_page(html)?;
+    let sample = root.join(format!("input/day{}_sample.txt", day));
+    let module = root.join(format!("src/day{}.rs", day));
+
+    if fs::metadata(&sample).is_ok_and(|file| file.len() > 0) {
+        let err = io::Error::new(ErrorKind::AlreadyExists, "refusing to overwrite an existing sample");
+        return Err(Error::from(err).in_file(&sample.to_string_lossy()));
+    }
+
+    let answer = |part: usize| page.answers.get(part).and_then(Option::as_deref);
+    let tests = scaffold::tests(day, [answer(0), answer(1)]);
+    let placeholder = scaffold::tests(day, [None, None]);
+
+    write(&sample, &page.samples[0])?;
+
+    match module.exists().then(|| read(&module)).transpose()? {
+        Some(source) if source.contains(&placeholder) => {
+            write(&module, &source.replace(&placeholder, &tests))?;
+            Ok(Extracted { files: vec![sample, module], stub: None })
+        },
+        _ => Ok(Extracted { files: vec![sample], stub: Some(tests) }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 1: Calorie Counting ---</h2><p>For example:</p>
+<pre><code>1000
+2000
+
+&lt;3000&gt;
+</code></pre>
+<p>This Elf is carrying the most: <code><em>24000</em></code> Calories.</p>
+<p><em>How many total Calories is that Elf carrying?</em></p>
+</article>
+<p>Your puzzle answer was <code>68923</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>In the example above, the top three Elves are carrying <em><code>45000</code></em> Calories.</p>
+</article>
+</main>"#;
+
+    #[test]
+    fn test_parse_page() {
+        let page = parse_page(PAGE).unwrap();
+        assert_eq!(vec!["1000\n2000\n\n<3000>\n".to_string()], page.samples);
+        assert_eq!(vec![Some("24000".to_string()), Some("45000".to_string())], page.answers);
+
+        assert!(parse_page("<html><body>404 Not Found</body></html>").is_err());
+    }
+
+    #[test]
+    fn test_extract() {
+        let root = std::env::temp_dir().join(format!("adventofcode-2022-extract-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("input")).unwrap();
+        fs::write(root.join("src/day19.rs"), scaffold::template(19)).unwrap();
+        fs::write(root.join("input/day19_sample.txt"), "").unwrap();
+
+        let extracted = extract(&root, 19, PAGE).unwrap();
+        assert_eq!(None, extracted.stub);
+        assert_eq!("1000\n2000\n\n<3000>\n", fs::read_to_string(root.join("input/day19_sample.txt")).unwrap());
+
+        let module = fs::read_to_string(root.join("src/day19.rs")).unwrap();
+        assert!(module.contains("assert_eq!(24000, part1(&input));"));
+        assert!(module.contains("assert_eq!(45000, part2(&input));"));
+
+        // the sample is there now, so it isn't overwritten.
+        assert!(extract(&root, 19, PAGE).is_err());
+
+        // a day without the template's tests gets a stub to add by hand.
+        let extracted = extract(&root, 20, PAGE).unwrap();
+        assert!(extracted.stub.unwrap().contains("assert_eq!(24000, part1(&input));"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,225 @@
+use std::env;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+
+/// SESSION_VAR is the environment variable that holds the adventofcode.com session token.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// SESSION_FILE is where the session token is read from if SESSION_VAR isn't set.  It's
+/// relative to the project root, and ignored by git.
+pub const SESSION_FILE: &str = ".session";
+
+/// BASE_URL_VAR is the environment variable that overrides the site inputs are fetched from.
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+
+/// BASE_URL is the site inputs are fetched from by default.
+pub const BASE_URL: &str = "https://adventofcode.com";
+
+/// Client downloads puzzle inputs for a logged in user.  The session is only needed to
+/// download an input, so a client without one can still find cached inputs.
+pub struct Client {
+    base_url: String,
+    session: Option<(String, SessionSource)>,
+}
+
+/// SessionSource is where a session token came from, so an expired one can be replaced there.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum SessionSource {
+    /// Var is the SESSION_VAR environment variable.
+    Var,
+
+    /// File is a SESSION_FILE.
+    File(PathBuf),
+}
+
+impl Display for SessionSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionSource::Var => write!(f, "{}", SESSION_VAR),
+            SessionSource::File(file) => write!(f, "{}", file.display()),
+        }
+    }
+}
+
+/// Fetched is where a day's input was saved, and whether it had to be downloaded.
+#[derive(Debug, Eq, PartialEq)]
+pub enum Fetched {
+    Downloaded(PathBuf),
+    Cached(PathBuf),
+}
+
+impl Client {
+    /// new returns a client for the site at base_url, like "https://adventofcode.com", with the
+    /// session token and where it came from.
+    pub fn new(base_url: &str, session: Option<(&str, SessionSource)>) -> Self {
+        Client {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.map(|(session, source)| (session.trim().to_string(), source)),
+        }
+    }
+
+    /// from_env returns a client configured by the environment.  The session token comes from
+    /// SESSION_VAR or else the SESSION_FILE in root, and the site from BASE_URL_VAR or else
+    /// BASE_URL.
+    pub fn from_env(root: &Path) -> Result<Self> {
+        let base_url = env::var(BASE_URL_VAR).unwrap_or_else(|_| BASE_URL.to_string());
+
+        let session = match env::var(SESSION_VAR) {
+            Ok(session) => Some((session, SessionSource::Var)),
+            Err(_) => {
+                let file = root.join(SESSION_FILE);
+
+                match fs::read_to_string(&file) {
+                    Ok(session) => Some((session, SessionSource::File(file))),
+                    Err(err) if err.kind() == ErrorKind::NotFound => None,
+                    Err(err) => return Err(Error::from(err).in_file(&file.to_string_lossy())),
+                }
+            },
+        };
+
+        let session = session.filter(|(session, _)| !session.trim().is_empty());
+        Ok(Self::new(&base_url, session.as_ref().map(|(session, source)| (session.as_str(), source.clone()))))
+    }
+
+    /// input downloads the puzzle input for the given day.
+    pub fn input(&self, day: u8) -> Result<String> {
+        let url = format!("{}/2022/day/{}/input", self.base_url, day);
+        let fail = |reason: &str| Error::Fetch { url: url.clone(), reason: reason.to_string() };
+
+        let Some((session, source)) = &self.session else {
+            return Err(fail(&format!("no session token - set {} or save it in {}", SESSION_VAR, SESSION_FILE)));
+        };
+
+        let agent = ureq::AgentBuilder::new().timeout(Duration::from_secs(30)).build();
+        let response = agent.get(&url)
+            .set("Cookie", &format!("session={}", session))
+            .set("User-Agent", "adventofcode-2022 input fetcher")
+            .call();
+
+        let expired = format!("the session token is invalid or has expired - log in again and update {}", source);
+
+        let input = match response {
+            Ok(response) => response.into_string().map_err(|err| fail(&err.to_string()))?,
+            Err(ureq::Error::Status(400 | 401 | 403, _)) => return Err(fail(&expired)),
+            Err(ureq::Error::Status(404, _)) => return Err(fail("the puzzle isn't unlocked yet")),
+            Err(ureq::Error::Status(code, _)) => return Err(fail(&format!("unexpected HTTP status {}", code))),
+            Err(ureq::Error::Transport(err)) => {
+                // the transport error's own message repeats the URL, so only its cause is kept.
+                let cause = std::error::Error::source(&err).map(|cause| cause.to_string());
+                return Err(fail(&cause.unwrap_or_else(|| err.kind().to_string())));
+            },
+        };
+
+        // logged out users are sometimes sent this message instead of an error status.
+        if input.starts_with("Puzzle inputs differ by user") {
+            return Err(fail(&expired));
+        }
+
+        Ok(input)
+    }
+}
+
+/// fetch saves the puzzle input for the given day to its input file in root, unless it's
+/// already there.  An empty input file, like the one new starts a day with, doesn't count.
+pub fn fetch(client: &Client, root: &Path, day: u8) -> Result<Fetched> {
+    let file = root.join(format!("input/day{}.txt", day));
+
+    if fs::metadata(&file).is_ok_and(|metadata| metadata.len() > 0) {
+        return Ok(Fetched::Cached(file));
+    }
+
+    let input = client.input(day)?;
+
+    fs::write(&file, input).map_err(|err| Error::from(err).in_file(&file.to_string_lossy()))?;
+
+    Ok(Fetched::Downloaded(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    use super::*;
+
+    /// serve answers a single request on a local port with the given status and body, and
+    /// returns the URL to reach it and a handle that returns the request's headers.
+    fn serve(status: &str, body: &'static str) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let status = status.to_string();
+
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let headers = BufReader::new(&stream).lines()
+                .map(|line| line.unwrap())
+                .take_while(|line| !line.is_empty())
+                .collect::<Vec<String>>();
+
+            write!(stream, "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", status, body.len(), body).unwrap();
+            headers
+        });
+
+        (url, handle)
+    }
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = env::temp_dir().join(format!("adventofcode-2022-{}-{}", name, std::process::id()));
+        fs::create_dir_all(root.join("input")).unwrap();
+        root
+    }
+
+    #[test]
+    fn test_fetch() {
+        let root = temp_root("fetch");
+        let (url, server) = serve("200 OK", "1000\n2000\n");
+        let client = Client::new(&url, Some(("abc123\n", SessionSource::Var)));
+
+        let file = root.join("input/day1.txt");
+        assert_eq!(Fetched::Downloaded(file.clone()), fetch(&client, &root, 1).unwrap());
+        assert_eq!("1000\n2000\n", fs::read_to_string(&file).unwrap());
+
+        let headers = server.join().unwrap();
+        assert_eq!("GET /2022/day/1/input HTTP/1.1", headers[0]);
+        assert!(headers.iter().any(|header| header.eq_ignore_ascii_case("cookie: session=abc123")));
+
+        // the server has gone, so this only works because the input isn't fetched again.
+        assert_eq!(Fetched::Cached(file), fetch(&client, &root, 1).unwrap());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_errors() {
+        let root = temp_root("fetch-errors");
+
+        let (url, server) = serve("400 Bad Request", "Puzzle inputs differ by user.  Please log in to get your puzzle input.");
+        let err = fetch(&Client::new(&url, Some(("expired", SessionSource::Var))), &root, 2).unwrap_err();
+        assert!(err.to_string().contains("invalid or has expired - log in again and update AOC_SESSION"), "{}", err);
+        assert!(!root.join("input/day2.txt").exists());
+        server.join().unwrap();
+
+        let (url, server) = serve("404 Not Found", "");
+        let err = fetch(&Client::new(&url, Some(("abc123", SessionSource::Var))), &root, 25).unwrap_err();
+        assert!(err.to_string().contains("isn't unlocked yet"), "{}", err);
+        server.join().unwrap();
+
+        // a token from the session file has to be replaced in the file, not the variable.
+        let file = root.join(SESSION_FILE);
+        let (url, server) = serve("200 OK", "Puzzle inputs differ by user.  Please log in to get your puzzle input.");
+        let err = fetch(&Client::new(&url, Some(("expired", SessionSource::File(file.clone())))), &root, 2).unwrap_err();
+        assert!(err.to_string().ends_with(&format!("log in again and update {}", file.display())), "{}", err);
+        server.join().unwrap();
+
+        let err = fetch(&Client::new(BASE_URL, None), &root, 3).unwrap_err();
+        assert!(err.to_string().contains("no session token"), "{}", err);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,619 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::ops::RangeInclusive;
+
+/// Rng is a small, seeded random number generator (SplitMix64), so generated inputs can be
+/// reproduced from their seed.  It's fast and well mixed, but not for anything cryptographic.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// new returns a generator that always produces the same numbers for the same seed.
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    /// next_u64 returns the next random number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// range returns a random number in the given range.
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let span = range.end().abs_diff(*range.start()) + 1;
+        range.start().wrapping_add((self.next_u64() % span) as i64)
+    }
+
+    /// below returns a random number from 0 up to, but not including, n.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// one_in returns true one time in n, on average.
+    pub fn one_in(&mut self, n: usize) -> bool {
+        self.below(n) == 0
+    }
+
+    /// choose returns a random item.
+    pub fn choose<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len())]
+    }
+
+    /// shuffle puts the items in a random order.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+
+    /// word returns a random lowercase word of the given length.
+    pub fn word(&mut self, len: usize) -> String {
+        (0..len).map(|_| (b'a' + self.below(26) as u8) as char).collect()
+    }
+}
+
+/// Generator returns a random puzzle input for a day.  The size is roughly how many things
+/// are in the input, like elves, rounds or sensors.
+pub type Generator = fn(&mut Rng, usize) -> String;
+
+/// generator returns the input generator for the given day, if it has one.
+pub fn generator(day: u8) -> Option<Generator> {
+    let generator: Generator = match day {
+        1 => calories,
+        2 => rounds,
+        3 => rucksacks,
+        4 => assignments,
+        5 => crates,
+        6 => datastream,
+        7 => terminal,
+        8 => trees,
+        9 => motions,
+        10 => program,
+        11 => monkeys,
+        12 => heightmap,
+        13 => packets,
+        14 => rock_paths,
+        15 => sensors,
+        16 => valves,
+        17 => jets,
+        21 => yells,
+        25 => snafu_numbers,
+        _ => return None,
+    };
+
+    Some(generator)
+}
+
+/// calories returns size elves' calories for day 1.
+pub fn calories(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| (0..rng.range(1..=8)).map(|_| format!("{}\n", rng.range(1..=60000))).collect::<String>())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// rounds returns size rounds of a rock paper scissors strategy guide for day 2.
+pub fn rounds(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| format!("{} {}\n", rng.choose(&['A', 'B', 'C']), rng.choose(&['X', 'Y', 'Z'])))
+        .collect()
+}
+
+/// rucksacks returns size groups of three rucksacks for day 3.  The compartments of each
+/// rucksack have exactly one item in common, and so do the rucksacks in each group.
+pub fn rucksacks(rng: &mut Rng, size: usize) -> String {
+    let mut items = ('a'..='z').chain('A'..='Z').collect::<Vec<char>>();
+    let mut output = String::new();
+
+    for _ in 0..size.max(1) {
+        rng.shuffle(&mut items);
+
+        // each elf in the group gets 17 items of their own, so only the badge is shared.
+        let badge = items[0];
+
+        for elf in items[1..].chunks_exact(17) {
+            let shared = if rng.one_in(3) { badge } else { elf[0] };
+            let own = elf.iter().copied().filter(|&item| item != shared).collect::<Vec<char>>();
+            let (first, second) = own.split_at(own.len() / 2);
+
+            // the badge goes in one compartment, unless it's the item they have in common.
+            let badge_in_first = rng.one_in(2);
+            let len = rng.below(9) + 2;
+
+            let mut compartment = |pool: &[char], badge_here: bool| {
+                let mut compartment = vec![shared];
+                if badge_here && shared != badge {
+                    compartment.push(badge);
+                }
+                while compartment.len() < len {
+                    compartment.push(rng.choose(pool));
+                }
+                rng.shuffle(&mut compartment);
+                compartment.into_iter().collect::<String>()
+            };
+
+            let first = compartment(first, badge_in_first);
+            let second = compartment(second, !badge_in_first);
+            writeln!(output, "{}{}", first, second).unwrap();
+        }
+    }
+
+    output
+}
+
+/// assignments returns size pairs of section assignments for day 4.
+pub fn assignments(rng: &mut Rng, size: usize) -> String {
+    let range = |rng: &mut Rng| {
+        let start = rng.range(1..=99);
+        format!("{}-{}", start, rng.range(start..=99))
+    };
+
+    (0..size.max(1)).map(|_| format!("{},{}\n", range(rng), range(rng))).collect()
+}
+
+/// crates returns a drawing of up to nine stacks of crates, and size moves between them, for
+/// day 5.  Every move takes at most the crates that are on the stack it moves them from.
+pub fn crates(rng: &mut Rng, size: usize) -> String {
+    let num_stacks = rng.below(9) + 1;
+    let mut stacks = (0..num_stacks)
+        .map(|_| (0..rng.below(size.max(1) + 1)).map(|_| (b'A' + rng.below(26) as u8) as char).collect::<Vec<char>>())
+        .collect::<Vec<Vec<char>>>();
+
+    if stacks.iter().all(Vec::is_empty) {
+        stacks[0].push('A');
+    }
+
+    let mut output = String::new();
+    let tallest = stacks.iter().map(Vec::len).max().unwrap_or(0);
+
+    for level in (0..tallest).rev() {
+        let row = stacks.iter()
+            .map(|stack| stack.get(level).map_or("   ".to_string(), |c| format!("[{}]", c)))
+            .collect::<Vec<String>>();
+        writeln!(output, "{}", row.join(" ")).unwrap();
+    }
+
+    let numbers = (1..=num_stacks).map(|num| format!(" {} ", num)).collect::<Vec<String>>();
+    writeln!(output, "{}\n", numbers.join(" ").trim_end()).unwrap();
+
+    for _ in 0..size.max(1) {
+        let from = loop {
+            let from = rng.below(num_stacks);
+            if !stacks[from].is_empty() {
+                break from;
+            }
+        };
+        let to = rng.below(num_stacks);
+        let num = rng.below(stacks[from].len()) + 1;
+
+        let keep = stacks[from].len() - num;
+        let moved = stacks[from].split_off(keep);
+        stacks[to].extend(moved);
+        writeln!(output, "move {} from {} to {}", num, from + 1, to + 1).unwrap();
+    }
+
+    output
+}
+
+/// datastream returns a datastream of at least size characters for day 6.  It has a run of 14
+/// different characters somewhere, so it has both a start-of-packet and start-of-message marker.
+pub fn datastream(rng: &mut Rng, size: usize) -> String {
+    let len = size.max(14);
+
+    // few letters, so markers don't turn up by chance too often.
+    let mut stream = (0..len).map(|_| (b'a' + rng.below(4) as u8) as char).collect::<Vec<char>>();
+
+    let mut marker = ('a'..='z').collect::<Vec<char>>();
+    rng.shuffle(&mut marker);
+
+    let at = rng.below(len - 13);
+    stream[at..at + 14].copy_from_slice(&marker[..14]);
+
+    stream.into_iter().chain(['\n']).collect()
+}
+
+/// terminal returns a transcript of browsing a filesystem with about size files and directories
+/// for day 7.  Every directory is listed before it's entered.  The files are small enough to
+/// leave room on the disk for an archive directory, whose size puts the disk over the space
+/// that's needed, but not by as much as the archive itself, so part 2 always has an answer.
+pub fn terminal(rng: &mut Rng, size: usize) -> String {
+    const DISK: u64 = 70_000_000;
+    const NEEDED: u64 = 30_000_000;
+
+    struct Browse {
+        entries: usize,
+        max_file: i64,
+        used: u64,
+    }
+
+    fn browse(rng: &mut Rng, state: &mut Browse, depth: usize, output: &mut String) {
+        writeln!(output, "$ ls").unwrap();
+
+        let mut dirs = HashSet::new();
+        let mut files = HashSet::new();
+
+        for _ in 0..rng.range(1..=5) {
+            if state.entries == 0 {
+                break;
+            }
+            state.entries -= 1;
+
+            if depth < 6 && rng.one_in(3) {
+                let len = rng.below(3) + 1;
+                let dir = rng.word(len);
+                if dirs.insert(dir.clone()) {
+                    writeln!(output, "dir {}", dir).unwrap();
+                }
+            } else {
+                let len = rng.below(6) + 1;
+                let file = format!("{}.{}", rng.word(len), rng.choose(&["txt", "log", "dat", "lst"]));
+                if files.insert(file.clone()) {
+                    let size = rng.range(1..=state.max_file);
+                    state.used += size as u64;
+                    writeln!(output, "{} {}", size, file).unwrap();
+                }
+            }
+        }
+
+        let mut dirs = dirs.into_iter().collect::<Vec<String>>();
+        dirs.sort();
+
+        for dir in dirs {
+            writeln!(output, "$ cd {}", dir).unwrap();
+            browse(rng, state, depth + 1, output);
+            writeln!(output, "$ cd ..").unwrap();
+        }
+    }
+
+    // the other files never fill more than the space that's needed, so there's room left for
+    // the archive to be bigger than the space it has to free.
+    let entries = size.max(1);
+    let mut state = Browse { entries, max_file: (NEEDED as i64 / entries as i64).min(300000), used: 0 };
+    let mut listing = String::new();
+    browse(rng, &mut state, 0, &mut listing);
+
+    // the archive's name is too long to be one of the random directories.
+    let archive = DISK - NEEDED - state.used + rng.range(1..=NEEDED as i64) as u64;
+    let listing = listing.replacen("$ ls\n", "$ ls\ndir archive\n", 1);
+
+    // browsing always ends back in the root directory.
+    format!("$ cd /\n{}$ cd archive\n$ ls\n{} backup.tar\n", listing, archive)
+}
+
+/// trees returns a size by size grid of tree heights for day 8.
+pub fn trees(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+
+    (0..size)
+        .map(|_| (0..size).map(|_| char::from_digit(rng.below(10) as u32, 10).unwrap()).chain(['\n']).collect::<String>())
+        .collect()
+}
+
+/// motions returns size moves of the head of the rope for day 9.
+pub fn motions(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| format!("{} {}\n", rng.choose(&['U', 'D', 'L', 'R']), rng.range(1..=20)))
+        .collect()
+}
+
+/// program returns a program of size instructions for day 10.
+pub fn program(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| if rng.one_in(3) { "noop\n".to_string() } else { format!("addx {}\n", rng.range(-10..=10)) })
+        .collect()
+}
+
+/// monkeys returns between 2 and 9 monkeys for day 11, depending on size.  Each monkey tests
+/// divisibility by a different prime and throws to two other monkeys, like the real puzzle.
+/// Monkeys multiply worry levels by at most 3, which relief undoes, so with relief they never
+/// get much over 100 and part 1 can't overflow.
+pub fn monkeys(rng: &mut Rng, size: usize) -> String {
+    let num_monkeys = size.clamp(2, 9);
+    let mut primes = [2, 3, 5, 7, 11, 13, 17, 19, 23];
+    rng.shuffle(&mut primes);
+
+    let mut output = String::new();
+
+    for (monkey, prime) in primes.iter().take(num_monkeys).enumerate() {
+        let items = (0..rng.range(1..=4)).map(|_| rng.range(50..=99).to_string()).collect::<Vec<String>>();
+
+        let operation = match rng.below(3) {
+            0 => format!("old * {}", rng.range(2..=3)),
+            1 => "old + old".to_string(),
+            _ => format!("old + {}", rng.range(1..=8)),
+        };
+
+        let mut other = || loop {
+            let other = rng.below(num_monkeys);
+            if other != monkey {
+                break other;
+            }
+        };
+
+        if monkey > 0 {
+            output.push('\n');
+        }
+
+        writeln!(output, "Monkey {}:", monkey).unwrap();
+        writeln!(output, "  Starting items: {}", items.join(", ")).unwrap();
+        writeln!(output, "  Operation: new = {}", operation).unwrap();
+        writeln!(output, "  Test: divisible by {}", prime).unwrap();
+        writeln!(output, "    If true: throw to monkey {}", other()).unwrap();
+        writeln!(output, "    If false: throw to monkey {}", other()).unwrap();
+    }
+
+    output
+}
+
+/// heightmap returns a heightmap for day 12 with about size rows.  There's always a way from
+/// S to E: along the top row and down the right side, climbing one letter at a time.
+pub fn heightmap(rng: &mut Rng, size: usize) -> String {
+    // the path has to be long enough to climb from a to z.
+    let height = size.max(1);
+    let width = (size * 2).max(27 - height.min(26));
+
+    let mut map = (0..height)
+        .map(|_| (0..width).map(|_| (b'a' + rng.below(26) as u8) as char).collect::<Vec<char>>())
+        .collect::<Vec<Vec<char>>>();
+
+    let path = (0..width).map(|col| (0, col)).chain((1..height).map(|row| (row, width - 1))).collect::<Vec<(usize, usize)>>();
+
+    for (i, &(row, col)) in path.iter().enumerate() {
+        map[row][col] = (b'a' + (i * 25 / (path.len() - 1)) as u8) as char;
+    }
+
+    map[0][0] = 'S';
+    map[height - 1][width - 1] = 'E';
+
+    map.into_iter().map(|row| row.into_iter().chain(['\n']).collect::<String>()).collect()
+}
+
+/// packets returns size pairs of packets for day 13.
+pub fn packets(rng: &mut Rng, size: usize) -> String {
+    fn packet(rng: &mut Rng, depth: usize) -> String {
+        let items = (0..rng.below(5))
+            .map(|_| if depth < 4 && rng.one_in(3) { packet(rng, depth + 1) } else { rng.range(0..=10).to_string() })
+            .collect::<Vec<String>>();
+
+        format!("[{}]", items.join(","))
+    }
+
+    (0..size.max(1))
+        .map(|_| format!("{}\n{}\n", packet(rng, 0), packet(rng, 0)))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// rock_paths returns size paths of rock for day 14, below where the sand pours in.
+pub fn rock_paths(rng: &mut Rng, size: usize) -> String {
+    let mut output = String::new();
+
+    for _ in 0..size.max(1) {
+        let (mut x, mut y) = (rng.range(470..=530), rng.range(2..=40));
+        let mut points = vec![format!("{},{}", x, y)];
+
+        for i in 0..rng.range(1..=4) {
+            // paths turn at every point, between horizontal and vertical.
+            if i % 2 == 0 {
+                x = (x + rng.range(-8..=8)).clamp(440, 560);
+            } else {
+                y = (y + rng.range(-8..=8)).clamp(2, 60);
+            }
+            points.push(format!("{},{}", x, y));
+        }
+
+        writeln!(output, "{}", points.join(" -> ")).unwrap();
+    }
+
+    output
+}
+
+/// sensors returns size sensors for day 15, in the area both parts search.  None of them
+/// reach a hidden spot, so there's always somewhere for the distress beacon to be.
+pub fn sensors(rng: &mut Rng, size: usize) -> String {
+    const MAX: i64 = 4000000;
+    let hidden = (rng.range(0..=MAX), rng.range(0..=MAX));
+    let mut output = String::new();
+
+    for _ in 0..size.max(1) {
+        let (x, y) = loop {
+            let sensor = (rng.range(0..=MAX), rng.range(0..=MAX));
+            if sensor != hidden {
+                break sensor;
+            }
+        };
+
+        // the beacon is closer than the hidden spot.
+        let reach = rng.range(0..=(x - hidden.0).abs() + (y - hidden.1).abs() - 1);
+        let dx = rng.range(-reach..=reach);
+        let dy = (reach - dx.abs()) * rng.choose(&[-1, 1]);
+
+        writeln!(output, "Sensor at x={}, y={}: closest beacon is at x={}, y={}", x, y, x + dx, y + dy).unwrap();
+    }
+
+    output
+}
+
+/// valves returns size valves for day 16, connected by tunnels so every valve can reach every
+/// other.  About a third of them have flow, at most 15 so the search stays quick.
+pub fn valves(rng: &mut Rng, size: usize) -> String {
+    let mut names = HashSet::from(["AA".to_string()]);
+    while names.len() < size.clamp(2, 26 * 26) {
+        names.insert(format!("{}{}", (b'A' + rng.below(26) as u8) as char, (b'A' + rng.below(26) as u8) as char));
+    }
+
+    let mut names = names.into_iter().filter(|name| name != "AA").collect::<Vec<String>>();
+    names.sort();
+    rng.shuffle(&mut names);
+    names.insert(0, "AA".to_string());
+
+    // a random tree keeps every valve connected, and a few more tunnels add loops.
+    let mut tunnels = vec![Vec::new(); names.len()];
+    let mut connect = |a: usize, b: usize| {
+        if a != b && !tunnels[a].contains(&b) {
+            tunnels[a].push(b);
+            tunnels[b].push(a);
+        }
+    };
+
+    for valve in 1..names.len() {
+        connect(valve, rng.below(valve));
+    }
+    for _ in 0..names.len() / 3 {
+        connect(rng.below(names.len()), rng.below(names.len()));
+    }
+
+    let mut flowing = 0;
+    let mut output = String::new();
+
+    for (valve, name) in names.iter().enumerate() {
+        let rate = if valve > 0 && flowing < 15 && rng.one_in(3) { rng.range(1..=25) } else { 0 };
+        flowing += usize::from(rate > 0);
+
+        let leads = tunnels[valve].iter().map(|&other| names[other].as_str()).collect::<Vec<&str>>();
+        let tunnels = match leads.as_slice() {
+            [one] => format!("tunnel leads to valve {}", one),
+            _ => format!("tunnels lead to valves {}", leads.join(", ")),
+        };
+
+        writeln!(output, "Valve {} has flow rate={}; {}", name, rate, tunnels).unwrap();
+    }
+
+    output
+}
+
+/// jets returns a jet pattern of size pushes for day 17.
+pub fn jets(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1)).map(|_| rng.choose(&['<', '>'])).chain(['\n']).collect()
+}
+
+/// yells returns about size monkeys for day 21.  humn is at the bottom of a chain of
+/// operations on one side of root, and the other side is the number that chain yells.  So the
+/// humn given is the answer to part 2, and every division along the way is exact.
+pub fn yells(rng: &mut Rng, size: usize) -> String {
+    let mut names = HashSet::from(["root".to_string(), "humn".to_string()]);
+    let mut name = |rng: &mut Rng| loop {
+        let name = rng.word(4);
+        if names.insert(name.clone()) {
+            break name;
+        }
+    };
+
+    let mut lines = Vec::new();
+    let mut number = |name: String, value: i64| {
+        lines.push(format!("{}: {}", name, value));
+        name
+    };
+
+    let humn = rng.range(1..=1000);
+    let (mut monkey, mut value) = (number("humn".to_string(), humn), humn);
+    let mut chain = Vec::new();
+
+    for _ in 0..size.max(1) {
+        let other = rng.range(1..=9);
+        let (yell, next) = match rng.below(4) {
+            0 => (format!("{} + {}", monkey, "{}"), value + other),
+            1 if rng.one_in(2) => (format!("{} - {}", monkey, "{}"), value - other),
+            1 => (format!("{} - {}", "{}", monkey), other - value),
+            2 if value.abs() < 1_000_000_000 => (format!("{} * {}", monkey, "{}"), value * other),
+            _ if value % other == 0 => (format!("{} / {}", monkey, "{}"), value / other),
+            _ => (format!("{} + {}", monkey, "{}"), value + other),
+        };
+
+        let other = number(name(rng), other);
+        let next_monkey = name(rng);
+        chain.push(format!("{}: {}", next_monkey, yell.replace("{}", &other)));
+        (monkey, value) = (next_monkey, next);
+    }
+
+    let other = number(name(rng), value);
+    let mut lines = lines.into_iter().chain(chain).collect::<Vec<String>>();
+    lines.push(format!("root: {} + {}", monkey, other));
+    rng.shuffle(&mut lines);
+
+    lines.into_iter().map(|line| line + "\n").collect()
+}
+
+/// snafu_numbers returns size SNAFU numbers for day 25.
+pub fn snafu_numbers(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            let rest = (0..rng.below(20)).map(|_| rng.choose(&['2', '1', '0', '-', '='])).collect::<String>();
+            format!("{}{}\n", rng.choose(&['1', '2']), rest)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use crate::registry::Part;
+    use crate::solution::Solution;
+
+    use super::*;
+
+    /// SIZES are the sizes of input each generator is checked with.
+    const SIZES: &[usize] = &[0, 1, 8];
+
+    /// check_solves checks that a day's solution parses every input its generator makes, and
+    /// answers both parts, for a range of seeds and the given sizes.
+    fn check_solves<S: Solution>(day: u8, sizes: &[usize]) {
+        let generate = generator(day).unwrap();
+
+        for seed in 0..10 {
+            for &size in sizes {
+                let input = generate(&mut Rng::new(seed), size);
+
+                if let Err(err) = registry::run::<S>(&input, Part::Both) {
+                    panic!("day {} with seed {} and size {}: {}\n{}", day, seed, size, err, input);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_generators_solve() {
+        check_solves::<day1::Day1>(1, SIZES);
+        check_solves::<day2::Day2>(2, SIZES);
+        check_solves::<day3::Day3>(3, SIZES);
+        check_solves::<day4::Day4>(4, SIZES);
+        check_solves::<day5::Day5>(5, SIZES);
+        check_solves::<day6::Day6>(6, SIZES);
+        check_solves::<day7::Day7>(7, SIZES);
+        check_solves::<day8::Day8>(8, SIZES);
+        check_solves::<day9::Day9>(9, SIZES);
+        check_solves::<day10::Day10>(10, SIZES);
+        check_solves::<day11::Day11>(11, SIZES);
+        check_solves::<day12::Day12>(12, SIZES);
+        check_solves::<day13::Day13>(13, SIZES);
+        check_solves::<day14::Day14>(14, SIZES);
+        // part 2 sweeps the rows until it finds one that isn't covered, which can take millions
+        // of rows when there are more sensors.
+        check_solves::<day15::Day15>(15, &[0, 1, 2]);
+        check_solves::<day16::Day16>(16, SIZES);
+        check_solves::<day17::Day17>(17, SIZES);
+        check_solves::<day21::Day21>(21, SIZES);
+        check_solves::<day25::Day25>(25, SIZES);
+    }
+
+    #[test]
+    fn test_rng() {
+        let numbers = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..10).map(|_| rng.range(-5..=5)).collect::<Vec<i64>>()
+        };
+
+        assert_eq!(numbers(7), numbers(7));
+        assert_ne!(numbers(7), numbers(8));
+        assert!(numbers(7).iter().all(|num| (-5..=5).contains(num)));
+
+        assert_eq!(generator(3).unwrap()(&mut Rng::new(1), 4), generator(3).unwrap()(&mut Rng::new(1), 4));
+        assert!(generator(18).is_none());
+    }
+}
@@ -0,0 +1,354 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::ops::{Add, AddAssign, Mul, Sub};
+
+use itertools::Itertools;
+
+use crate::error::{Error, Result};
+
+/// Point is a position on a grid, where rows count downward and columns count to the right.
+/// Points are signed so they can step off the edge of a grid, or be used with sparse grids
+/// that don't have edges.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone, Default)]
+pub struct Point {
+    pub row: i64,
+    pub col: i64,
+}
+
+impl Point {
+    pub const UP: Point = Point::at(-1, 0);
+    pub const DOWN: Point = Point::at(1, 0);
+    pub const LEFT: Point = Point::at(0, -1);
+    pub const RIGHT: Point = Point::at(0, 1);
+
+    /// ORTHOGONAL are the directions to the 4 neighbors that share an edge with a point.
+    pub const ORTHOGONAL: [Point; 4] = [Point::UP, Point::DOWN, Point::LEFT, Point::RIGHT];
+
+    /// ADJACENT are the directions to the 8 neighbors that share an edge or corner with a point.
+    pub const ADJACENT: [Point; 8] = [
+        Point::at(-1, -1), Point::UP, Point::at(-1, 1),
+        Point::LEFT, Point::RIGHT,
+        Point::at(1, -1), Point::DOWN, Point::at(1, 1),
+    ];
+
+    pub const fn at(row: i64, col: i64) -> Self {
+        Point { row, col }
+    }
+
+    /// manhattan returns the Manhattan distance between two points.
+    pub fn manhattan(&self, other: Point) -> i64 {
+        (self.row - other.row).abs() + (self.col - other.col).abs()
+    }
+}
+
+impl Debug for Point {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.row, self.col)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point::at(self.row + rhs.row, self.col + rhs.col)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Point::at(self.row - rhs.row, self.col - rhs.col)
+    }
+}
+
+impl Mul<i64> for Point {
+    type Output = Point;
+
+    fn mul(self, rhs: i64) -> Self::Output {
+        Point::at(self.row * rhs, self.col * rhs)
+    }
+}
+
+/// Grid is a dense, rectangular grid of cells, stored row by row.  Row 0 is the top of the grid.
+#[derive(Clone, Eq, PartialEq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+}
+
+impl<T> Grid<T> {
+    /// new returns a grid of the given size with every cell set to value.
+    pub fn new(height: usize, width: usize, value: T) -> Self where T: Clone {
+        Grid { cells: vec![value; height * width], width }
+    }
+
+    /// parse parses a grid with a character for each cell, one row per line.  Every row
+    /// must be the same width, and there must be at least one row.  One empty line at the end,
+    /// like editors often leave, is ignored.
+    pub fn parse(input: &str, cell: impl Fn(char) -> Result<T>) -> Result<Self> {
+        let input = input.strip_suffix("\r\n").or_else(|| input.strip_suffix('\n')).unwrap_or(input);
+        let mut grid = Grid { cells: Vec::new(), width: 0 };
+
+        for (i, line) in input.lines().enumerate() {
+            if line.is_empty() {
+                return Err(Error::parse(line, "empty row").at_line(i + 1));
+            }
+
+            let row = line.chars().map(&cell).collect::<Result<Vec<T>>>().map_err(|err| err.at_line(i + 1))?;
+            grid.push_row(row).map_err(|_| Error::parse(line, "rows must be the same width").at_line(i + 1))?;
+        }
+
+        if grid.cells.is_empty() {
+            return Err(Error::parse("", "empty grid"));
+        }
+
+        Ok(grid)
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len().checked_div(self.width).unwrap_or(0)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// contains returns whether the point is inside the grid.
+    pub fn contains(&self, point: Point) -> bool {
+        self.index(point).is_some()
+    }
+
+    /// get returns the cell at the given point, or None if it's outside the grid.
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index(point).map(|i| &self.cells[i])
+    }
+
+    /// get_mut returns the cell at the given point, or None if it's outside the grid.
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index(point).map(|i| &mut self.cells[i])
+    }
+
+    /// push_row adds a row to the bottom of the grid.  The first row sets the width of the grid,
+    /// and it's an error for a later row to be a different width.
+    pub fn push_row(&mut self, row: impl IntoIterator<Item = T>) -> Result<()> {
+        let row = row.into_iter().collect_vec();
+
+        if self.cells.is_empty() {
+            self.width = row.len();
+        } else if row.len() != self.width {
+            return Err(Error::solve(format!("rows must be {} wide, not {}", self.width, row.len())));
+        }
+
+        self.cells.extend(row);
+        Ok(())
+    }
+
+    /// grow adds rows of the given value to the bottom of the grid, until it's the given height.
+    pub fn grow(&mut self, height: usize, value: T) where T: Clone {
+        if height > self.height() {
+            self.cells.resize(height * self.width, value);
+        }
+    }
+
+    /// points returns every point in the grid, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width as i64;
+        (0..self.cells.len() as i64).map(move |i| Point::at(i / width, i % width))
+    }
+
+    /// iter returns every point in the grid with its cell, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    /// find returns the first point whose cell matches the predicate, row by row.
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<Point> {
+        self.iter().find(|(_, cell)| predicate(cell)).map(|(point, _)| point)
+    }
+
+    /// rows returns the cells of each row, from the top of the grid.
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + ExactSizeIterator {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// row returns the cells in the given row, from left to right, or None if it's outside the
+    /// grid.
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        (row < self.height()).then(|| &self.cells[row * self.width..(row + 1) * self.width])
+    }
+
+    /// column returns the cells in the given column, from top to bottom, or None if it's outside
+    /// the grid.
+    pub fn column(&self, col: usize) -> Option<impl DoubleEndedIterator<Item = &T>> {
+        (col < self.width).then(|| self.cells.iter().skip(col).step_by(self.width))
+    }
+
+    /// ray returns the points from start (not including start) in the given direction, stopping
+    /// at the edge of the grid.
+    pub fn ray(&self, start: Point, dir: Point) -> impl Iterator<Item = Point> + '_ {
+        (1..).map(move |i| start + dir * i).take_while(|&point| self.contains(point))
+    }
+
+    /// neighbors returns the 4 points that share an edge with the given point, if they're
+    /// inside the grid.
+    pub fn neighbors(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.around(point, &Point::ORTHOGONAL)
+    }
+
+    /// adjacent returns the 8 points that share an edge or corner with the given point, if
+    /// they're inside the grid.
+    pub fn adjacent(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.around(point, &Point::ADJACENT)
+    }
+
+    fn around<'a>(&'a self, point: Point, dirs: &'a [Point]) -> impl Iterator<Item = Point> + 'a {
+        dirs.iter().map(move |&dir| point + dir).filter(|&neighbor| self.contains(neighbor))
+    }
+
+    fn index(&self, point: Point) -> Option<usize> {
+        let (row, col) = (usize::try_from(point.row).ok()?, usize::try_from(point.col).ok()?);
+
+        if col < self.width && row < self.height() {
+            Some(row * self.width + col)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Debug> Debug for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.rows()).finish()
+    }
+}
+
+/// SparseGrid is a grid that only stores the cells that have been set, for grids that are mostly
+/// empty or don't have a fixed size.
+#[derive(Clone, Debug, Default)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        SparseGrid { cells: HashMap::new() }
+    }
+
+    /// insert sets the cell at the given point, returning the cell that was there before.
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        self.cells.insert(point, value)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    /// contains returns whether the cell at the given point has been set.
+    pub fn contains(&self, point: Point) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    /// len returns the number of cells that have been set.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// iter returns the cells that have been set, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(&point, value)| (point, value))
+    }
+
+    /// bounds returns the top left and bottom right corners of the cells that have been set,
+    /// or None if the grid is empty.
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        let rows = self.cells.keys().map(|point| point.row).minmax().into_option()?;
+        let cols = self.cells.keys().map(|point| point.col).minmax().into_option()?;
+
+        Some((Point::at(rows.0, cols.0), Point::at(rows.1, cols.1)))
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        SparseGrid { cells: iter.into_iter().collect() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits() -> Grid<u32> {
+        Grid::parse("123\n456", |c| c.to_digit(10).ok_or_else(|| Error::parse(&c.to_string(), "not a digit")))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_grid() {
+        let grid = digits();
+
+        assert_eq!((2, 3), (grid.height(), grid.width()));
+        assert_eq!(Some(&6), grid.get(Point::at(1, 2)));
+        assert_eq!(None, grid.get(Point::at(2, 0)));
+        assert_eq!(None, grid.get(Point::at(0, -1)));
+        assert_eq!(Some(&[4, 5, 6][..]), grid.row(1));
+        assert_eq!(None, grid.row(2));
+        assert_eq!(vec![2, 5], grid.column(1).unwrap().copied().collect_vec());
+        assert!(grid.column(3).is_none());
+
+        assert_eq!(vec![Point::at(1, 0), Point::at(0, 1)], grid.neighbors(Point::at(0, 0)).collect_vec());
+        assert_eq!(5, grid.adjacent(Point::at(0, 1)).count());
+        assert_eq!(vec![Point::at(0, 1), Point::at(0, 2)], grid.ray(Point::at(0, 0), Point::RIGHT).collect_vec());
+        assert_eq!(Some(Point::at(1, 1)), grid.find(|&cell| cell == 5));
+    }
+
+    #[test]
+    fn test_grid_push_row() {
+        let mut grid = Grid::new(0, 3, 0);
+        grid.push_row([1, 2]).unwrap();
+        grid.grow(3, 0);
+
+        assert_eq!(Some(&[0, 0][..]), grid.row(2));
+        assert_eq!("rows must be 2 wide, not 3", grid.push_row([1, 2, 3]).unwrap_err().to_string());
+        assert_eq!(3, grid.height());
+    }
+
+    #[test]
+    fn test_grid_parse_invalid() {
+        let digit = |c: char| c.to_digit(10).ok_or_else(|| Error::parse(&c.to_string(), "not a digit"));
+
+        assert_eq!("2: not a digit: 'x'", Grid::parse("12\n1x", digit).unwrap_err().to_string());
+        assert_eq!("2: rows must be the same width: '123'", Grid::parse("12\n123", digit).unwrap_err().to_string());
+        assert_eq!("empty grid", Grid::parse("", digit).unwrap_err().to_string());
+    }
+
+    #[test]
+    fn test_grid_parse_trailing_line() {
+        let digit = |c: char| c.to_digit(10).ok_or_else(|| Error::parse(&c.to_string(), "not a digit"));
+
+        assert_eq!(2, Grid::parse("12\n34\n\n", digit).unwrap().height());
+        assert_eq!(2, Grid::parse("12\r\n34\r\n\r\n", digit).unwrap().height());
+        assert_eq!("3: empty row", Grid::parse("12\n34\n\n\n", digit).unwrap_err().to_string());
+    }
+
+    #[test]
+    fn test_sparse_grid() {
+        let grid: SparseGrid<char> = [(Point::at(3, -2), '#'), (Point::at(-1, 4), '#')].into_iter().collect();
+
+        assert_eq!(2, grid.len());
+        assert!(grid.contains(Point::at(3, -2)));
+        assert_eq!(Some((Point::at(-1, -2), Point::at(3, 4))), grid.bounds());
+    }
+}
@@ -0,0 +1,195 @@
+use std::fmt::{Debug, Formatter};
+use std::ops::RangeInclusive;
+
+/// IntervalSet is a set of integers stored as ranges.  The ranges are kept normalized: sorted,
+/// with no ranges that overlap or touch, so two sets are equal exactly when they hold the same
+/// numbers.
+#[derive(Eq, PartialEq, Clone, Default)]
+pub struct IntervalSet {
+    ranges: Vec<RangeInclusive<i64>>,
+}
+
+impl IntervalSet {
+    /// new returns an empty set.
+    pub fn new() -> Self {
+        IntervalSet { ranges: Vec::new() }
+    }
+
+    /// insert adds the numbers in range to the set.  Empty ranges, like 3..=2, add nothing.
+    pub fn insert(&mut self, range: RangeInclusive<i64>) {
+        let (mut start, mut end) = range.into_inner();
+        if start > end {
+            return
+        }
+
+        // the ranges from first to last overlap or touch the new range, so they're merged with it.
+        let first = self.ranges.partition_point(|r| r.end().saturating_add(1) < start);
+        let last = self.ranges.partition_point(|r| *r.start() <= end.saturating_add(1));
+
+        if first < last {
+            start = start.min(*self.ranges[first].start());
+            end = end.max(*self.ranges[last - 1].end());
+        }
+
+        self.ranges.splice(first..last, [start..=end]);
+    }
+
+    /// remove takes the numbers in range out of the set.
+    pub fn remove(&mut self, range: RangeInclusive<i64>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return
+        }
+
+        // the ranges from first to last overlap the removed range, so only their ends are kept.
+        let first = self.ranges.partition_point(|r| *r.end() < start);
+        let last = self.ranges.partition_point(|r| *r.start() <= end);
+        if first == last {
+            return
+        }
+
+        let before = start.checked_sub(1).map(|end| *self.ranges[first].start()..=end);
+        let after = end.checked_add(1).map(|start| start..=*self.ranges[last - 1].end());
+
+        self.ranges.splice(first..last, [before, after].into_iter().flatten().filter(|r| !r.is_empty()));
+    }
+
+    /// union returns the numbers in either set.
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut union = self.clone();
+        other.iter().for_each(|range| union.insert(range));
+        union
+    }
+
+    /// intersection returns the numbers in both sets.
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let (mut i, mut j) = (0, 0);
+        let mut ranges = Vec::new();
+
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let overlap = *a.start().max(b.start())..=*a.end().min(b.end());
+            if !overlap.is_empty() {
+                ranges.push(overlap);
+            }
+
+            // whichever range ends first can't overlap anything else in the other set.
+            if a.end() < b.end() {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        IntervalSet { ranges }
+    }
+
+    /// difference returns the numbers in this set that aren't in the other.
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut difference = self.clone();
+        other.iter().for_each(|range| difference.remove(range));
+        difference
+    }
+
+    /// contains returns whether the number is in the set.
+    pub fn contains(&self, num: i64) -> bool {
+        self.contains_range(&(num..=num))
+    }
+
+    /// contains_range returns whether every number in range is in the set.
+    pub fn contains_range(&self, range: &RangeInclusive<i64>) -> bool {
+        if range.is_empty() {
+            return true
+        }
+
+        let i = self.ranges.partition_point(|r| r.end() < range.start());
+        self.ranges.get(i).is_some_and(|r| r.start() <= range.start() && range.end() <= r.end())
+    }
+
+    /// is_superset returns whether every number in the other set is in this one.
+    pub fn is_superset(&self, other: &IntervalSet) -> bool {
+        other.ranges.iter().all(|range| self.contains_range(range))
+    }
+
+    /// is_empty returns whether there are no numbers in the set.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// len returns how many numbers are in the set.  It's a u128, since a set of every i64 has
+    /// one more number than a u64 can count.
+    pub fn len(&self) -> u128 {
+        self.ranges.iter().map(|range| (*range.end() as i128 - *range.start() as i128 + 1) as u128).sum()
+    }
+
+    /// iter returns the set's normalized ranges, in order.
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<i64>> + '_ {
+        self.ranges.iter().cloned()
+    }
+}
+
+impl From<RangeInclusive<i64>> for IntervalSet {
+    fn from(range: RangeInclusive<i64>) -> Self {
+        let mut set = IntervalSet::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl FromIterator<RangeInclusive<i64>> for IntervalSet {
+    fn from_iter<T: IntoIterator<Item = RangeInclusive<i64>>>(ranges: T) -> Self {
+        let mut set = IntervalSet::new();
+        ranges.into_iter().for_each(|range| set.insert(range));
+        set
+    }
+}
+
+impl Debug for IntervalSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.ranges.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+
+    #[test]
+    fn test_insert_remove() {
+        let mut set = IntervalSet::from_iter([10..=12, 1..=3, 5..=6, 4..=4, RangeInclusive::new(20, 19)]);
+        assert_eq!(vec![1..=6, 10..=12], set.iter().collect_vec());
+        assert_eq!(9, set.len());
+
+        set.remove(2..=2);
+        set.remove(5..=11);
+        assert_eq!(vec![1..=1, 3..=4, 12..=12], set.iter().collect_vec());
+
+        set.insert(i64::MAX - 1..=i64::MAX);
+        set.insert(i64::MIN..=0);
+        assert_eq!(vec![i64::MIN..=1, 3..=4, 12..=12, i64::MAX - 1..=i64::MAX], set.iter().collect_vec());
+        assert_eq!((1 << 63) + 2 + 2 + 1 + 2, set.len());
+
+        set.remove(i64::MIN..=0);
+        set.remove(i64::MAX..=i64::MAX);
+        assert_eq!(vec![1..=1, 3..=4, 12..=12, i64::MAX - 1..=i64::MAX - 1], set.iter().collect_vec());
+
+        set.insert(i64::MIN..=i64::MAX);
+        assert_eq!(1 << 64, set.len());
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = IntervalSet::from_iter([1..=5, 10..=15]);
+        let b = IntervalSet::from_iter([4..=11, 15..=20]);
+
+        assert_eq!(IntervalSet::from_iter([1..=20]), a.union(&b));
+        assert_eq!(IntervalSet::from_iter([4..=5, 10..=11, 15..=15]), a.intersection(&b));
+        assert_eq!(IntervalSet::from_iter([1..=3, 12..=14]), a.difference(&b));
+
+        assert!(a.contains(13) && !a.contains(7));
+        assert!(a.contains_range(&(10..=15)) && !a.contains_range(&(5..=10)));
+        assert!(a.union(&b).is_superset(&a) && !a.is_superset(&b));
+        assert!(a.difference(&a).is_empty());
+    }
+}
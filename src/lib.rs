@@ -0,0 +1,42 @@
+//! Solutions to the 2022 Advent of Code.  Each day's puzzle is a module with a type that
+//! implements [`solution::Solution`], along with the parsers, part functions and types it's
+//! built from.  The [`registry`] lists every solved day, for running them by number.
+
+pub mod answers;
+pub mod bench;
+pub mod error;
+pub mod extract;
+pub mod fetch;
+pub mod generate;
+pub mod grid;
+pub mod intervals;
+pub mod registry;
+pub mod report;
+pub mod scaffold;
+pub mod search;
+pub mod solution;
+
+/// differential checks the solutions and types that rely on clever invariants against naive
+/// references, on generated inputs.
+#[cfg(test)]
+mod differential;
+
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day21;
+pub mod day25;
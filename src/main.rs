@@ -1,24 +1,385 @@
 extern crate core;
 
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day17;
-mod day21;
-mod day25;
+use std::fs;
+use std::fs::{read_to_string, File};
+use std::io;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::exit;
+use std::time::{Duration, Instant};
+
+use adventofcode_2022::{answers, bench, day1, extract, fetch, generate, registry, scaffold};
+use adventofcode_2022::answers::{ANSWERS_FILE, KnownAnswers};
+use adventofcode_2022::bench::{Stats, Timings};
+use adventofcode_2022::day1::CalorieStats;
+use adventofcode_2022::error::{Error, Result};
+use adventofcode_2022::fetch::Fetched;
+use adventofcode_2022::generate::Rng;
+use adventofcode_2022::registry::{Answers, Day, Part};
+use adventofcode_2022::report::{Kind, Record};
+use cli::{Args, Command, Days, Format};
+use rayon::prelude::*;
+
+mod cli;
 
 fn main() {
-    day17::solution();
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+
+    let args = match cli::parse_args(&args) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, cli::USAGE);
+            exit(2);
+        }
+    };
+
+    if let (Command::New, Days::One(num)) = (args.command, args.days) {
+        match scaffold::scaffold(Path::new("."), num) {
+            Ok(files) => files.iter().for_each(|file| println!("{}", file.display())),
+            Err(err) => {
+                eprintln!("Day {}: {}", num, err);
+                exit(1);
+            },
+        }
+
+        return;
+    }
+
+    if let (Command::Fetch, Days::One(num)) = (args.command, args.days) {
+        let fetched = fetch::Client::from_env(Path::new("."))
+            .and_then(|client| fetch::fetch(&client, Path::new("."), num));
+
+        match fetched {
+            Ok(Fetched::Downloaded(file)) => println!("{}: downloaded", file.display()),
+            Ok(Fetched::Cached(file)) => println!("{}: already downloaded", file.display()),
+            Err(err) => {
+                eprintln!("Day {}: {}", num, err);
+                exit(1);
+            },
+        }
+
+        return;
+    }
+
+    if let (Command::Generate { size, seed }, Days::One(num)) = (args.command, args.days) {
+        let Some(generate) = generate::generator(num) else {
+            eprintln!("Day {} doesn't have an input generator", num);
+            exit(1);
+        };
+
+        print!("{}", generate(&mut Rng::new(seed), size));
+        return;
+    }
+
+    if let (Command::Extract, Days::One(num), Some(page)) = (args.command, args.days, &args.input) {
+        let extracted = read_input(page)
+            .and_then(|(name, html)| extract::extract(Path::new("."), num, &html).map_err(|err| err.in_file(name)));
+
+        match extracted {
+            Ok(extracted) => {
+                extracted.files.iter().for_each(|file| println!("{}", file.display()));
+
+                if let Some(stub) = extracted.stub {
+                    println!("\nsrc/day{}.rs already has its own tests, so add these by hand:\n\n{}", num, stub);
+                }
+            },
+            Err(err) => {
+                eprintln!("Day {}: {}", num, err);
+                exit(1);
+            },
+        }
+
+        return;
+    }
+
+    if let Command::Stats { top } = args.command {
+        let filename = args.input.clone().unwrap_or_else(|| "input/day1.txt".to_string());
+
+        match open_input(&filename).and_then(|(name, reader)| day1::analyze(reader, top).map_err(|err| err.in_file(name))) {
+            Ok(stats) => print_stats(&stats),
+            Err(err) => {
+                eprintln!("Day 1: {}", err);
+                exit(1);
+            },
+        }
+
+        return;
+    }
+
+    let days = match args.days {
+        Days::All => registry::days().into_iter()
+            .map(|day| (day.input(), day))
+            .collect::<Vec<(String, Day)>>(),
+        Days::One(num) => {
+            let Some(day) = registry::find(num) else {
+                eprintln!("Day {} hasn't been solved", num);
+                exit(1);
+            };
+
+            vec![(args.input.clone().unwrap_or_else(|| day.input()), day)]
+        },
+    };
+
+    let mut known = match args.command {
+        Command::Verify { record: false } => KnownAnswers::load(ANSWERS_FILE),
+        Command::Verify { record: true } => KnownAnswers::load(ANSWERS_FILE).or_else(|err| {
+            // the answers are about to be replaced, so a broken file isn't a problem.
+            eprintln!("{}: ignoring", err);
+            Ok(KnownAnswers::default())
+        }),
+        _ => Ok(KnownAnswers::default()),
+    }.unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        exit(1);
+    });
+
+    // Days run concurrently, except when benching, where they'd compete for the CPU and skew
+    // each other's timings.  The outcomes are collected in order, so the output is the same
+    // however the days were scheduled.
+    let started = Instant::now();
+    let outcomes = match args.command {
+        Command::Bench { .. } => days.iter().map(|(input, day)| outcome(day, input, &args)).collect(),
+        _ => days.par_iter().map(|(input, day)| outcome(day, input, &args)).collect::<Vec<Outcome>>(),
+    };
+    let skipped = outcomes.iter().filter(|outcome| matches!(outcome, Outcome::Skipped)).count();
+    let elapsed = started.elapsed();
+
+    if let Command::Bench { .. } = args.command {
+        println!("{}", bench::HEADER);
+    }
+
+    let mut failures = Vec::new();
+
+    for ((_, day), outcome) in days.iter().zip(outcomes) {
+        match report(day, outcome, &args, &mut known) {
+            Ok(true) => {},
+            Ok(false) => failures.push(day.number),
+            Err(err) => {
+                eprintln!("Day {}: {}", day.number, err);
+                failures.push(day.number);
+            },
+        }
+    }
+
+    if args.days == Days::All {
+        eprintln!("{}", summary(days.len() - skipped, &failures, elapsed));
+    }
+
+    let mut failed = !failures.is_empty();
+
+    if let Command::Verify { record: true } = args.command {
+        if let Err(err) = known.save(ANSWERS_FILE) {
+            eprintln!("{}", err);
+            failed = true;
+        }
+    }
+
+    if failed {
+        exit(1);
+    }
+}
+
+/// Outcome is the result of running a command for a day, before it's printed.
+enum Outcome {
+    Answers(Result<Answers>),
+    Records(Vec<Record>),
+    Timings(Result<Timings>),
+    Skipped,
+}
+
+/// outcome runs the command for a day's puzzle against the given input file.  When running
+/// every day, days without a puzzle input are skipped.
+fn outcome(day: &Day, filename: &str, args: &Args) -> Outcome {
+    // Some days are only solved against their samples, and have no puzzle input.  New days
+    // start with an empty input until the puzzle input is added.
+    if args.days == Days::All && !fs::metadata(filename).is_ok_and(|file| file.len() > 0) {
+        return Outcome::Skipped;
+    }
+
+    match args.command {
+        Command::Run if args.format == Format::Json => Outcome::Records(records(day, filename, args.part)),
+        Command::Run | Command::Verify { .. } => Outcome::Answers(answer(day, filename, args.part)),
+        Command::Bench { runs } => Outcome::Timings(timings(day, filename, args.part, runs)),
+        // main handles these before looking up any days, since they don't run one.
+        Command::New | Command::Fetch | Command::Extract | Command::Generate { .. } | Command::Stats { .. } => unreachable!("{:?} doesn't run a day", args.command),
+    }
+}
+
+/// report prints the outcome of running the command for a day.  It returns false if verify
+/// found answers that don't match the known answers, or a JSON report has errors.  A skipped
+/// day isn't a failure, but a JSON report still has a record for each of its parts saying why.
+fn report(day: &Day, outcome: Outcome, args: &Args, known: &mut KnownAnswers) -> Result<bool> {
+    match (outcome, args.command) {
+        (Outcome::Skipped, Command::Run) if args.format == Format::Json => {
+            let error = format!("skipped, {} is missing or empty", day.input());
+            print_records(&failed_records(day, args.part, &error));
+            Ok(true)
+        },
+        (Outcome::Skipped, _) => {
+            eprintln!("Day {}: skipped, {} is missing or empty", day.number, day.input());
+            Ok(true)
+        },
+        (Outcome::Answers(answers), Command::Verify { record }) => Ok(verify_day(day, &answers?, known, record)),
+        (Outcome::Answers(answers), _) => print_answers(day, &answers?).map(|_| true),
+        (Outcome::Records(records), _) => Ok(print_records(&records)),
+        (Outcome::Timings(timings), _) => print_timings(day, timings?).map(|_| true),
+    }
+}
+
+/// print_answers prints the answers to the selected parts of a day's puzzle.
+fn print_answers(day: &Day, answers: &Answers) -> Result<()> {
+    println!("Day {}", day.number);
+    for (num, answer) in [(1, &answers.part1), (2, &answers.part2)] {
+        match answer {
+            // Image answers, like day 10's, span multiple lines.
+            Some(answer) if day.kinds[num - 1] == Kind::Image => println!("Part {}:\n{}", num, answer),
+            Some(answer) => println!("Part {}: {}", num, answer),
+            None => {},
+        }
+    }
+
+    Ok(())
+}
+
+/// records answers the selected parts of a day's puzzle against the given input file, with a
+/// record for each part.  The input is parsed once, and each part is timed on its own, so the
+/// elapsed times don't include parsing.  Errors are reported in the records rather than
+/// returned, so a report always has a record for every part.
+fn records(day: &Day, filename: &str, part: Part) -> Vec<Record> {
+    let timed = read_input(filename).and_then(|(name, input)| {
+        (day.timed_run)(&input, part).map(|timed| (name, timed)).map_err(|err| err.in_file(name))
+    });
+
+    let (name, timed) = match timed {
+        Ok(timed) => timed,
+        Err(err) => return failed_records(day, part, &err.to_string()),
+    };
+
+    [(1, timed.part1), (2, timed.part2)].into_iter()
+        .filter_map(|(num, answer)| answer.map(|answer| (num, answer)))
+        .map(|(num, (answer, elapsed))| match answer {
+            Ok(answer) => Record { day: day.number, part: num, answer: Some(answer), kind: day.kinds[num as usize - 1], elapsed, error: None },
+            Err(err) => Record { day: day.number, part: num, answer: None, kind: day.kinds[num as usize - 1], elapsed, error: Some(err.in_file(name).to_string()) },
+        })
+        .collect()
+}
+
+/// failed_records returns a record with the given error for each selected part of a day's
+/// puzzle, when none of them could be answered.
+fn failed_records(day: &Day, part: Part, error: &str) -> Vec<Record> {
+    [(1, part.one() && day.parts.one()), (2, part.two() && day.parts.two())].into_iter()
+        .filter(|(_, selected)| *selected)
+        .map(|(num, _)| Record { day: day.number, part: num, answer: None, kind: day.kinds[num as usize - 1], elapsed: Duration::ZERO, error: Some(error.to_string()) })
+        .collect()
+}
+
+/// print_records prints each record as a line of JSON.  It returns false if any part failed.
+fn print_records(records: &[Record]) -> bool {
+    for record in records {
+        println!("{}", record.to_json());
+    }
+
+    records.iter().all(|record| record.error.is_none())
+}
+
+/// timings times the selected parts of a day's puzzle against the given input file.
+fn timings(day: &Day, filename: &str, part: Part, runs: usize) -> Result<Timings> {
+    let (name, input) = read_input(filename)?;
+
+    (day.bench)(&input, part, bench::WARMUP_RUNS, runs).map_err(|err| err.in_file(name))
+}
+
+/// print_timings prints a row of the timings table for each phase of a day's puzzle.
+fn print_timings(day: &Day, timings: Timings) -> Result<()> {
+    for (phase, timings) in [("parse", timings.parse), ("part 1", timings.part1), ("part 2", timings.part2)] {
+        if let Some(stats) = Stats::of(&timings) {
+            println!("{}", bench::row(day.number, phase, stats));
+        }
+    }
+
+    Ok(())
+}
+
+/// print_stats prints statistics of the elves' total calories, with a histogram of the totals.
+fn print_stats(stats: &CalorieStats) {
+    let show = |num: Option<u64>| num.map_or("-".to_string(), |num| num.to_string());
+
+    println!("Elves: {}", stats.count);
+    println!("Mean: {}", stats.mean().map_or("-".to_string(), |mean| format!("{:.1}", mean)));
+    println!("Min: {}", show(stats.min));
+    println!("Median: {}", show(stats.median()));
+    println!("Max: {}", show(stats.max));
+
+    for percentile in [90.0, 99.0] {
+        println!("p{}: {}", percentile, show(stats.percentile(percentile)));
+    }
+
+    let top = stats.top.values();
+    println!("Top {}: {}", top.len(), top.iter().map(u64::to_string).collect::<Vec<String>>().join(", "));
+
+    println!("Histogram:");
+    for (range, count) in stats.histogram.buckets() {
+        println!("  {:>10}..={:<10} {}", range.start(), range.end(), count);
+    }
+}
+
+/// verify_day prints whether the answers to a day's puzzle match the known answers, and returns
+/// whether they all do.  If record is set, the answers are recorded as the known answers instead.
+fn verify_day(day: &Day, answers: &Answers, known: &mut KnownAnswers, record: bool) -> bool {
+    if record {
+        known.record(day.number, answers);
+        println!("Day {}: recorded", day.number);
+        return true;
+    }
+
+    let differences = answers::differences(known.get(day.number), answers);
+    if differences.is_empty() {
+        println!("Day {}: ok", day.number);
+    }
+
+    for difference in &differences {
+        println!("Day {}: {}", day.number, difference);
+    }
+
+    differences.is_empty()
+}
+
+/// summary describes how a run of every day went, like "18 days in 1.2s: 17 ok, 1 failed (15)".
+fn summary(days: usize, failures: &[u8], elapsed: Duration) -> String {
+    let mut summary = format!("{} days in {:.1?}: {} ok, {} failed", days, elapsed, days - failures.len(), failures.len());
+
+    if !failures.is_empty() {
+        summary.push_str(&format!(" ({})", failures.iter().map(u8::to_string).collect::<Vec<String>>().join(", ")));
+    }
+
+    summary
+}
+
+/// answer reads the input file and answers the selected parts of a day's puzzle.
+fn answer(day: &Day, filename: &str, part: Part) -> Result<Answers> {
+    let (name, input) = read_input(filename)?;
+
+    (day.run)(&input, part).map_err(|err| err.in_file(name))
+}
+
+/// open_input returns the name of the input file and a reader that reads it a line at a time.
+/// The input is read from stdin if the filename is '-'.
+fn open_input(filename: &str) -> Result<(&str, Box<dyn BufRead>)> {
+    match filename {
+        "-" => Ok(("<stdin>", Box::new(io::stdin().lock()))),
+        _ => File::open(filename)
+            .map(|file| (filename, Box::new(BufReader::new(file)) as Box<dyn BufRead>))
+            .map_err(|err| Error::from(err).in_file(filename)),
+    }
+}
+
+/// read_input returns the name and contents of the input file.  The input is read from stdin
+/// if the filename is '-'.
+fn read_input(filename: &str) -> Result<(&str, String)> {
+    let (name, input) = match filename {
+        "-" => ("<stdin>", io::read_to_string(io::stdin().lock())),
+        _ => (filename, read_to_string(filename)),
+    };
+
+    input.map(|input| (name, input)).map_err(|err| Error::from(err).in_file(name))
 }
@@ -0,0 +1,200 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+use crate::*;
+use crate::bench::Timings;
+use crate::error::{Error, Result};
+use crate::report::{Answer, Kind};
+use crate::solution::Solution;
+
+/// Part selects which parts of a day's puzzle to run.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Part {
+    One, Two, Both
+}
+
+impl Part {
+    /// one returns whether part 1 should run.
+    pub fn one(&self) -> bool {
+        *self != Part::Two
+    }
+
+    /// two returns whether part 2 should run.
+    pub fn two(&self) -> bool {
+        *self != Part::One
+    }
+}
+
+/// Answers are the answers to the parts of a puzzle that were run.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Answers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+/// run parses the puzzle input and answers the selected parts of the puzzle, if the puzzle has
+/// them.  A solution that panics, like one that hasn't been finished yet, returns an error
+/// instead, so one broken day can't stop the others.
+pub fn run<S: Solution>(input: &str, part: Part) -> Result<Answers> {
+    catch_panic(|| {
+        let parsed = S::parse(input)?;
+
+        Ok(Answers {
+            part1: (part.one() && S::PARTS.one()).then(|| S::part1(&parsed).map(|answer| answer.to_string())).transpose()?,
+            part2: (part.two() && S::PARTS.two()).then(|| S::part2(&parsed).map(|answer| answer.to_string())).transpose()?,
+        })
+    })
+}
+
+/// TimedAnswers are the answers to the parts of a puzzle that were run, each with how long it
+/// took to answer, not counting parsing the input.  Each part has its own answer or error.
+#[derive(Debug)]
+pub struct TimedAnswers {
+    pub parse: Duration,
+    pub part1: Option<(Result<String>, Duration)>,
+    pub part2: Option<(Result<String>, Duration)>,
+}
+
+/// timed_run is like run, but times parsing and each part separately, like bench does once.
+/// The input is only parsed once, so it's an error if it can't be, but a part that fails or
+/// panics doesn't stop the other being answered.
+pub fn timed_run<S: Solution>(input: &str, part: Part) -> Result<TimedAnswers> {
+    let (parsed, parse) = bench::time(|| catch_panic(|| S::parse(input)));
+    let parsed = parsed?;
+
+    let answer = |solve: fn(&S::Input) -> Result<String>| bench::time(|| catch_panic(|| solve(&parsed)));
+
+    Ok(TimedAnswers {
+        parse,
+        part1: (part.one() && S::PARTS.one()).then(|| answer(|parsed| S::part1(parsed).map(|answer| answer.to_string()))),
+        part2: (part.two() && S::PARTS.two()).then(|| answer(|parsed| S::part2(parsed).map(|answer| answer.to_string()))),
+    })
+}
+
+/// bench is like [`bench::bench`], but returns an error if the solution panics.
+pub fn bench<S: Solution>(input: &str, part: Part, warmup: usize, runs: usize) -> Result<Timings> {
+    catch_panic(|| bench::bench::<S>(input, part, warmup, runs))
+}
+
+/// catch_panic returns the result of f, or an error with the panic's message if it panics.
+fn catch_panic<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        // panic! with a format string panics with a String, and with a literal, a &str.
+        let message = payload.downcast_ref::<String>().map(String::as_str)
+            .or_else(|| payload.downcast_ref::<&str>().copied())
+            .unwrap_or("unknown panic");
+
+        Err(Error::Panic { message: message.to_string() })
+    })
+}
+
+/// Day is a registered puzzle solution.  run answers the selected parts of the puzzle
+/// for the contents of an input file, timed_run answers them with how long each took, and
+/// bench times them over repeated runs.  parts are the parts the puzzle has, and kinds are the
+/// kinds of their answers.
+pub struct Day {
+    pub number: u8,
+    pub parts: Part,
+    pub kinds: [Kind; 2],
+    pub run: fn(&str, Part) -> Result<Answers>,
+    pub timed_run: fn(&str, Part) -> Result<TimedAnswers>,
+    pub bench: fn(&str, Part, usize, usize) -> Result<Timings>,
+}
+
+impl Day {
+    /// new registers the solution for the given day.
+    fn new<S: Solution>(number: u8) -> Self {
+        Day { number, parts: S::PARTS, kinds: [S::Part1::KIND, S::Part2::KIND], run: run::<S>, timed_run: timed_run::<S>, bench: bench::<S> }
+    }
+
+    /// input returns the path to this day's puzzle input.
+    pub fn input(&self) -> String {
+        format!("input/day{}.txt", self.number)
+    }
+}
+
+/// days returns every solved day, in order.
+pub fn days() -> Vec<Day> {
+    vec![
+        Day::new::<day1::Day1>(1),
+        Day::new::<day2::Day2>(2),
+        Day::new::<day3::Day3>(3),
+        Day::new::<day4::Day4>(4),
+        Day::new::<day5::Day5>(5),
+        Day::new::<day6::Day6>(6),
+        Day::new::<day7::Day7>(7),
+        Day::new::<day8::Day8>(8),
+        Day::new::<day9::Day9>(9),
+        Day::new::<day10::Day10>(10),
+        Day::new::<day11::Day11>(11),
+        Day::new::<day12::Day12>(12),
+        Day::new::<day13::Day13>(13),
+        Day::new::<day14::Day14>(14),
+        Day::new::<day15::Day15>(15),
+        Day::new::<day16::Day16>(16),
+        Day::new::<day17::Day17>(17),
+        Day::new::<day21::Day21>(21),
+        Day::new::<day25::Day25>(25),
+    ]
+}
+
+/// find returns the registered day with the given number, if it's been solved.
+pub fn find(number: u8) -> Option<Day> {
+    days().into_iter().find(|day| day.number == number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        let input = include_str!("../input/day1_sample.txt");
+
+        let expected = Answers { part1: Some("24000".to_string()), part2: Some("45000".to_string()) };
+        assert_eq!(expected, run::<day1::Day1>(input, Part::Both).unwrap());
+
+        let expected = Answers { part1: None, part2: Some("45000".to_string()) };
+        assert_eq!(expected, run::<day1::Day1>(input, Part::Two).unwrap());
+
+        // the rucksack parses, but its compartments don't share an item.
+        let err = run::<day3::Day3>("ab\n", Part::Both).unwrap_err();
+        assert!(matches!(err, Error::Parse { ref reason, .. } if reason.starts_with("no item is shared")), "{}", err);
+
+        // day 25 doesn't have a second part to answer.
+        let expected = Answers { part1: Some("1=".to_string()), part2: None };
+        assert_eq!(expected, run::<day25::Day25>("1=
+", Part::Both).unwrap());
+    }
+
+    #[test]
+    fn test_timed_run() {
+        let timed = timed_run::<day1::Day1>(include_str!("../input/day1_sample.txt"), Part::Two).unwrap();
+        assert!(timed.part1.is_none());
+        assert_eq!("45000", timed.part2.unwrap().0.unwrap());
+
+        // part 1 fails for the rucksack, but part 2 is still answered.
+        let timed = timed_run::<day3::Day3>("ab\nbc\nbd\n", Part::Both).unwrap();
+        assert!(timed.part1.unwrap().0.is_err());
+        assert_eq!("2", timed.part2.unwrap().0.unwrap());
+
+        assert!(timed_run::<day1::Day1>("lots\n", Part::Both).is_err());
+    }
+
+    #[test]
+    fn test_catch_panic() {
+        assert_eq!(3, catch_panic(|| Ok(3)).unwrap());
+
+        let err = catch_panic::<u32>(|| todo!()).unwrap_err();
+        assert!(matches!(err, Error::Panic { ref message } if message == "not yet implemented"));
+
+        let err = catch_panic::<u32>(|| panic!("no beacon in row {}", 10)).unwrap_err();
+        assert_eq!("panicked: no beacon in row 10", err.to_string());
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(Some(12), find(12).map(|day| day.number));
+        assert_eq!(None, find(0).map(|day| day.number));
+    }
+}
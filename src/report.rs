@@ -0,0 +1,129 @@
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+/// Kind is what an answer looks like, so tools reading a report know how to compare it.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Kind {
+    /// Integer answers are whole numbers, like most days' answers.
+    Integer,
+
+    /// String answers are a single line of text, like day 5's crates.
+    String,
+
+    /// Image answers are letters drawn over multiple lines, like day 10's screen.
+    Image,
+}
+
+impl Kind {
+    /// name returns the name of the kind in a report.
+    pub fn name(self) -> &'static str {
+        match self {
+            Kind::Integer => "integer",
+            Kind::String => "string",
+            Kind::Image => "image",
+        }
+    }
+}
+
+/// Answer is implemented by the types of the answers to each part of a puzzle, so a report
+/// knows what kind of answer it has without having to guess from the text.
+pub trait Answer: Display {
+    const KIND: Kind;
+}
+
+macro_rules! integer_answers {
+    ($($int:ty),*) => {
+        $(impl Answer for $int {
+            const KIND: Kind = Kind::Integer;
+        })*
+    };
+}
+
+integer_answers!(i32, i64, u32, u64, u128, usize);
+
+impl Answer for String {
+    const KIND: Kind = Kind::String;
+}
+
+/// Image is an answer drawn as letters over multiple lines, like day 10's screen.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Image(pub String);
+
+impl Display for Image {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Answer for Image {
+    const KIND: Kind = Kind::Image;
+}
+
+/// Record is the outcome of answering one part of a day's puzzle.  If the part couldn't be
+/// answered, there's an error instead of an answer.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<String>,
+    pub kind: Kind,
+    pub elapsed: Duration,
+    pub error: Option<String>,
+}
+
+impl Record {
+    /// to_json returns the record as a JSON object, like:
+    ///
+    /// ```json
+    /// {"answer":"24000","day":1,"elapsed_ms":0.012,"error":null,"part":1,"type":"integer"}
+    /// ```
+    ///
+    /// Answers are always strings, even integers, since some are too big for every JSON reader
+    /// to hold exactly.  The type is null when there's no answer.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "day": self.day,
+            "part": self.part,
+            "answer": self.answer,
+            "type": self.answer.as_ref().map(|_| self.kind.name()),
+            "elapsed_ms": self.elapsed.as_secs_f64() * 1000.0,
+            "error": self.error,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind() {
+        assert_eq!(Kind::Integer, u64::KIND);
+        assert_eq!(Kind::Integer, i64::KIND);
+        assert_eq!(Kind::String, String::KIND);
+        assert_eq!(Kind::Image, Image::KIND);
+        assert_eq!("##..\n..##", Image("##..\n..##".to_string()).to_string());
+    }
+
+    #[test]
+    fn test_to_json() {
+        let record = Record {
+            day: 1,
+            part: 2,
+            answer: Some("45000".to_string()),
+            kind: Kind::Integer,
+            elapsed: Duration::from_micros(1500),
+            error: None,
+        };
+
+        assert_eq!(
+            r#"{"answer":"45000","day":1,"elapsed_ms":1.5,"error":null,"part":2,"type":"integer"}"#,
+            record.to_json().to_string());
+
+        let failed = Record { answer: None, error: Some("invalid elf".to_string()), ..record };
+        assert_eq!(Value::Null, failed.to_json()["type"]);
+        assert_eq!(json!("invalid elf"), failed.to_json()["error"]);
+    }
+}
@@ -0,0 +1,222 @@
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+use crate::error::{Error, Result};
+
+/// TEMPLATE is the starting point for a new day's module.  DAY is replaced with the day number.
+const TEMPLATE: &str = r#"use crate::error::Result;
+use crate::solution::Solution;
+
+pub struct DayDAY;
+
+impl Solution for DayDAY {
+    type Input = Vec<String>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1> {
+        Ok(part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2> {
+        Ok(part2(input))
+    }
+}
+
+/// parse_input parses the puzzle input, one line at a time.
+pub fn parse_input(input: &str) -> Result<Vec<String>> {
+    Ok(input.lines().map(String::from).collect())
+}
+
+/// part1 returns the answer to the first part of the puzzle.
+pub fn part1(_input: &[String]) -> usize {
+    todo!()
+}
+
+/// part2 returns the answer to the second part of the puzzle.
+pub fn part2(_input: &[String]) -> usize {
+    todo!()
+}
+
+"#;
+
+/// TESTS_TEMPLATE is the starting point for a new day's tests.  They're ignored until the day is
+/// solved.  DAY is replaced with the day number, and PART1 and PART2 with the expected answers
+/// to the sample.
+const TESTS_TEMPLATE: &str = r#"#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[ignore = "not solved yet"]
+    fn test_part1() {
+        let input = DayDAY::parse(include_str!("../input/dayDAY_sample.txt")).unwrap();
+        assert_eq!(PART1, part1(&input));
+    }
+
+    #[test]
+    #[ignore = "not solved yet"]
+    fn test_part2() {
+        let input = DayDAY::parse(include_str!("../input/dayDAY_sample.txt")).unwrap();
+        assert_eq!(PART2, part2(&input));
+    }
+}
+"#;
+
+/// template returns the source of a new module for the given day.
+pub fn template(day: u8) -> String {
+    TEMPLATE.replace("DAY", &day.to_string()) + &tests(day, [None, None])
+}
+
+/// tests returns the tests for a new day's module, which check the answers to the sample.
+/// Unknown answers are left as 0, to be filled in by hand.
+pub fn tests(day: u8, answers: [Option<&str>; 2]) -> String {
+    let literal = |answer: Option<&str>| match answer {
+        Some(answer) if integer(answer) => answer.to_string(),
+        Some(answer) => format!("{:?}", answer),
+        None => "0".to_string(),
+    };
+
+    TESTS_TEMPLATE.replace("DAY", &day.to_string())
+        .replace("PART1", &literal(answers[0]))
+        .replace("PART2", &literal(answers[1]))
+}
+
+/// integer returns whether an answer is a whole number, which the tests compare as a number
+/// rather than a string.
+fn integer(answer: &str) -> bool {
+    let digits = answer.strip_prefix('-').unwrap_or(answer);
+
+    !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+/// scaffold starts a new day in the project at root.  It creates the day's module from the
+/// template, with empty puzzle and sample inputs, and registers the module in lib.rs and the
+/// registry.  Nothing is written if any of the files already exist, or the day is already
+/// registered.  It returns the files that were created or changed.
+pub fn scaffold(root: &Path, day: u8) -> Result<Vec<PathBuf>> {
+    let module = root.join(format!("src/day{}.rs", day));
+    let input = root.join(format!("input/day{}.txt", day));
+    let sample = root.join(format!("input/day{}_sample.txt", day));
+    let lib = root.join("src/lib.rs");
+    let registry = root.join("src/registry.rs");
+
+    for file in [&module, &input, &sample] {
+        if file.exists() {
+            let err = io::Error::new(ErrorKind::AlreadyExists, "refusing to overwrite an existing file");
+            return Err(Error::from(err).in_file(&file.to_string_lossy()));
+        }
+    }
+
+    // work out both registrations before writing anything, so a failure leaves no changes.
+    let new_lib = register(&read(&lib)?, r"pub mod day(\d+);", &format!("pub mod day{};", day), day)
+        .map_err(|err| err.in_file(&lib.to_string_lossy()))?;
+
+    let new_registry = register(
+        &read(&registry)?,
+        r"Day::new::<day\d+::Day\d+>\((\d+)\),",
+        &format!("Day::new::<day{0}::Day{0}>({0}),", day),
+        day,
+    ).map_err(|err| err.in_file(&registry.to_string_lossy()))?;
+
+    write(&module, &template(day))?;
+    write(&input, "")?;
+    write(&sample, "")?;
+    write(&lib, &new_lib)?;
+    write(&registry, &new_registry)?;
+
+    Ok(vec![module, input, sample, lib, registry])
+}
+
+/// register inserts a line into source that's kept in order by day, like the module
+/// declarations in lib.rs.  Existing lines match pattern, which captures their day number.
+/// The new line is indented like the line it's inserted next to.
+fn register(source: &str, pattern: &str, line: &str, day: u8) -> Result<String> {
+    let re = Regex::new(&format!(r"^(\s*){}\s*$", pattern)).unwrap();
+
+    let days = source.lines().enumerate()
+        .filter_map(|(i, text)| re.captures(text).map(|captures| (i, captures)))
+        .map(|(i, captures)| (i, captures[1].to_string(), captures[2].parse::<u8>().unwrap_or(0)))
+        .collect::<Vec<(usize, String, u8)>>();
+
+    if days.iter().any(|(_, _, registered)| *registered == day) {
+        return Err(Error::parse(line, "already registered"));
+    }
+
+    // insert before the first day after the new one, or after the last day.
+    let (at, indent) = match days.iter().find(|(_, _, registered)| *registered > day) {
+        Some((i, indent, _)) => (*i, indent),
+        None => match days.last() {
+            Some((i, indent, _)) => (i + 1, indent),
+            None => return Err(Error::parse(line, "no days are registered to insert it next to")),
+        },
+    };
+
+    let mut lines = source.lines().map(String::from).collect::<Vec<String>>();
+    lines.insert(at, format!("{}{}", indent, line));
+
+    let mut result = lines.join("\n");
+    if source.ends_with('\n') {
+        result.push('\n');
+    }
+
+    Ok(result)
+}
+
+pub(crate) fn read(file: &Path) -> Result<String> {
+    fs::read_to_string(file).map_err(|err| Error::from(err).in_file(&file.to_string_lossy()))
+}
+
+pub(crate) fn write(file: &Path, contents: &str) -> Result<()> {
+    fs::write(file, contents).map_err(|err| Error::from(err).in_file(&file.to_string_lossy()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register() {
+        let lib = "pub mod solution;\n\npub mod day1;\npub mod day2;\npub mod day10;\n";
+
+        assert_eq!(
+            "pub mod solution;\n\npub mod day1;\npub mod day2;\npub mod day5;\npub mod day10;\n",
+            register(lib, r"pub mod day(\d+);", "pub mod day5;", 5).unwrap());
+        assert_eq!(
+            "pub mod solution;\n\npub mod day1;\npub mod day2;\npub mod day10;\npub mod day11;\n",
+            register(lib, r"pub mod day(\d+);", "pub mod day11;", 11).unwrap());
+        assert!(register(lib, r"pub mod day(\d+);", "pub mod day2;", 2).is_err());
+
+        let registry = "    vec![\n        Day::new::<day1::Day1>(1),\n    ]";
+        assert_eq!(
+            "    vec![\n        Day::new::<day1::Day1>(1),\n        Day::new::<day3::Day3>(3),\n    ]",
+            register(registry, r"Day::new::<day\d+::Day\d+>\((\d+)\),", "Day::new::<day3::Day3>(3),", 3).unwrap());
+    }
+
+    #[test]
+    fn test_scaffold() {
+        let root = std::env::temp_dir().join(format!("adventofcode-2022-scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("input")).unwrap();
+        fs::write(root.join("src/lib.rs"), "pub mod day1;\n").unwrap();
+        fs::write(root.join("src/registry.rs"), "vec![\n    Day::new::<day1::Day1>(1),\n]\n").unwrap();
+
+        assert_eq!(5, scaffold(&root, 18).unwrap().len());
+        assert_eq!(template(18), fs::read_to_string(root.join("src/day18.rs")).unwrap());
+        assert_eq!("", fs::read_to_string(root.join("input/day18_sample.txt")).unwrap());
+        assert_eq!("pub mod day1;\npub mod day18;\n", fs::read_to_string(root.join("src/lib.rs")).unwrap());
+
+        // the day's files exist now, so it won't be scaffolded again.
+        let err = scaffold(&root, 18).unwrap_err();
+        assert!(matches!(err, Error::Io { ref err, .. } if err.kind() == ErrorKind::AlreadyExists));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,231 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// bfs returns the number of steps and the path from start to the closest node that satisfies
+/// goal, where every step to a neighbor costs 1.  The path includes start and the goal.
+pub fn bfs<N, I>(start: N, neighbors: impl FnMut(&N) -> I, goal: impl FnMut(&N) -> bool) -> Option<(usize, Vec<N>)>
+    where N: Eq + Hash + Clone, I: IntoIterator<Item = N>
+{
+    bfs_multi([start], neighbors, goal)
+}
+
+/// bfs_multi is like bfs, but searches from every start at once and returns the path from the
+/// start that's closest to a goal.
+pub fn bfs_multi<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut goal: impl FnMut(&N) -> bool,
+) -> Option<(usize, Vec<N>)>
+    where N: Eq + Hash + Clone, I: IntoIterator<Item = N>
+{
+    // parents[node] = the node that was visited before it, or None for a start.
+    let mut parents: HashMap<N, Option<N>> = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        if !parents.contains_key(&start) {
+            parents.insert(start.clone(), None);
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(node) = queue.pop_front() {
+        if goal(&node) {
+            let path = path_to(node, |node| parents[node].clone());
+            return Some((path.len() - 1, path));
+        }
+
+        for neighbor in neighbors(&node) {
+            if !parents.contains_key(&neighbor) {
+                parents.insert(neighbor.clone(), Some(node.clone()));
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    None
+}
+
+/// bfs_reverse searches backward from end, using a function that returns the nodes that can
+/// step to a node, to find the closest node that satisfies is_start.  The returned path runs
+/// forward, from that start to end.
+pub fn bfs_reverse<N, I>(end: N, predecessors: impl FnMut(&N) -> I, is_start: impl FnMut(&N) -> bool) -> Option<(usize, Vec<N>)>
+    where N: Eq + Hash + Clone, I: IntoIterator<Item = N>
+{
+    bfs(end, predecessors, is_start).map(reversed)
+}
+
+/// dijkstra returns the lowest cost and the path from start to a node that satisfies goal, where
+/// neighbors returns each neighbor with the cost of stepping to it.  The path includes start and
+/// the goal.
+pub fn dijkstra<N, C, I>(start: N, neighbors: impl FnMut(&N) -> I, goal: impl FnMut(&N) -> bool) -> Option<(C, Vec<N>)>
+    where N: Eq + Hash + Clone, C: Ord + Copy + Default + Add<Output = C>, I: IntoIterator<Item = (N, C)>
+{
+    astar_multi([start], neighbors, |_| C::default(), goal)
+}
+
+/// dijkstra_multi is like dijkstra, but searches from every start at once and returns the path
+/// from the start with the lowest cost to a goal.
+pub fn dijkstra_multi<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbors: impl FnMut(&N) -> I,
+    goal: impl FnMut(&N) -> bool,
+) -> Option<(C, Vec<N>)>
+    where N: Eq + Hash + Clone, C: Ord + Copy + Default + Add<Output = C>, I: IntoIterator<Item = (N, C)>
+{
+    astar_multi(starts, neighbors, |_| C::default(), goal)
+}
+
+/// dijkstra_reverse searches backward from end, using a function that returns the nodes that
+/// can step to a node and what that step costs, to find the cheapest node that satisfies
+/// is_start.  The returned path runs forward, from that start to end.
+pub fn dijkstra_reverse<N, C, I>(end: N, predecessors: impl FnMut(&N) -> I, is_start: impl FnMut(&N) -> bool) -> Option<(C, Vec<N>)>
+    where N: Eq + Hash + Clone, C: Ord + Copy + Default + Add<Output = C>, I: IntoIterator<Item = (N, C)>
+{
+    dijkstra(end, predecessors, is_start).map(reversed)
+}
+
+/// astar is like dijkstra, but is guided toward the goal by a heuristic that estimates the cost
+/// from a node to the goal.  The heuristic must never overestimate the cost, or the path might
+/// not be the cheapest.
+pub fn astar<N, C, I>(
+    start: N,
+    neighbors: impl FnMut(&N) -> I,
+    heuristic: impl FnMut(&N) -> C,
+    goal: impl FnMut(&N) -> bool,
+) -> Option<(C, Vec<N>)>
+    where N: Eq + Hash + Clone, C: Ord + Copy + Default + Add<Output = C>, I: IntoIterator<Item = (N, C)>
+{
+    astar_multi([start], neighbors, heuristic, goal)
+}
+
+/// astar_multi is like astar, but searches from every start at once.
+pub fn astar_multi<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut goal: impl FnMut(&N) -> bool,
+) -> Option<(C, Vec<N>)>
+    where N: Eq + Hash + Clone, C: Ord + Copy + Default + Add<Output = C>, I: IntoIterator<Item = (N, C)>
+{
+    // Nodes aren't ordered, so the heap holds indexes into visited instead of the nodes.
+    // visited[i] = (node, index of the node visited before it, cost from a start)
+    let mut visited: Vec<(N, Option<usize>, C)> = Vec::new();
+
+    // best[node] = index into visited of the cheapest way found to the node so far.
+    let mut best: HashMap<N, usize> = HashMap::new();
+    let mut heap = BinaryHeap::new();
+
+    for start in starts {
+        if !best.contains_key(&start) {
+            best.insert(start.clone(), visited.len());
+            heap.push(Reverse((heuristic(&start), C::default(), visited.len())));
+            visited.push((start, None, C::default()));
+        }
+    }
+
+    while let Some(Reverse((_, cost, i))) = heap.pop() {
+        let node = visited[i].0.clone();
+
+        // Already found a cheaper way to this node.
+        if best[&node] != i {
+            continue;
+        }
+
+        if goal(&node) {
+            let path = path_to(i, |&i| visited[i].1);
+            return Some((cost, path.into_iter().map(|i| visited[i].0.clone()).collect()));
+        }
+
+        for (neighbor, step) in neighbors(&node) {
+            let next = cost + step;
+
+            if best.get(&neighbor).is_none_or(|&j| next < visited[j].2) {
+                best.insert(neighbor.clone(), visited.len());
+                heap.push(Reverse((next + heuristic(&neighbor), next, visited.len())));
+                visited.push((neighbor, Some(i), next));
+            }
+        }
+    }
+
+    None
+}
+
+/// path_to returns the path that ends at node, following parent back to a node without one.
+fn path_to<N>(node: N, mut parent: impl FnMut(&N) -> Option<N>) -> Vec<N> {
+    let mut path = vec![node];
+
+    while let Some(prev) = parent(path.last().unwrap()) {
+        path.push(prev);
+    }
+
+    path.reverse();
+    path
+}
+
+/// reversed reverses the path found by a reverse search, so it runs forward.
+fn reversed<C, N>((cost, mut path): (C, Vec<N>)) -> (C, Vec<N>) {
+    path.reverse();
+    (cost, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bfs() {
+        // 0 -> 1 -> 2 -> 3, with a shortcut 0 -> 2, and 4 unreachable.
+        let edges = |&node: &u32| match node {
+            0 => vec![1, 2],
+            1 => vec![2],
+            2 => vec![3],
+            _ => vec![],
+        };
+
+        assert_eq!(Some((2, vec![0, 2, 3])), bfs(0, edges, |&node| node == 3));
+        assert_eq!(Some((1, vec![1, 2])), bfs_multi([1, 0], edges, |&node| node == 2));
+        assert_eq!(Some((2, vec![0, 2, 3])), bfs_reverse(3, |&node: &u32| match node {
+            3 => vec![2],
+            2 => vec![1, 0],
+            1 => vec![0],
+            _ => vec![],
+        }, |&node| node == 0));
+        assert_eq!(None, bfs(0, edges, |&node| node == 4));
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        // the direct road to 3 is more expensive than going the long way round.
+        let edges = |&node: &u32| match node {
+            0 => vec![(1, 1), (3, 10)],
+            1 => vec![(2, 2)],
+            2 => vec![(3, 3)],
+            _ => vec![],
+        };
+
+        assert_eq!(Some((6, vec![0, 1, 2, 3])), dijkstra(0, edges, |&node| node == 3));
+        assert_eq!(Some((5, vec![1, 2, 3])), dijkstra_multi([0, 1], edges, |&node| node == 3));
+        assert_eq!(None, dijkstra(3, edges, |&node| node == 0));
+    }
+
+    #[test]
+    fn test_astar() {
+        // walk around a wall on a 5x5 grid, with the Manhattan distance as the heuristic.
+        let wall = [(1, 1), (1, 2), (1, 3)];
+        let neighbors = |&(row, col): &(i32, i32)| {
+            [(row - 1, col), (row + 1, col), (row, col - 1), (row, col + 1)].into_iter()
+                .filter(|&(r, c)| (0..5).contains(&r) && (0..5).contains(&c) && !wall.contains(&(r, c)))
+                .map(|point| (point, 1))
+                .collect::<Vec<_>>()
+        };
+        let heuristic = |&(row, col): &(i32, i32)| (row - 2).abs() + (col - 2).abs();
+
+        let (cost, path) = astar((0, 2), neighbors, heuristic, |&point| point == (2, 2)).unwrap();
+        assert_eq!(6, cost);
+        assert_eq!(7, path.len());
+        assert_eq!((Some(&(0, 2)), Some(&(2, 2))), (path.first(), path.last()));
+    }
+}
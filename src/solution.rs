@@ -0,0 +1,70 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use crate::error::Result;
+use crate::registry::Part;
+use crate::report::Answer;
+
+/// Solution is implemented by every day's puzzle.  A puzzle's input is parsed once, and both
+/// parts are answered from the parsed input.
+pub trait Solution {
+    type Input;
+    type Part1: Answer;
+    type Part2: Answer;
+
+    /// PARTS are the parts the puzzle has.  Every day has two, except day 25, whose second star
+    /// is awarded for finishing the other days rather than for an answer.
+    const PARTS: Part = Part::Both;
+
+    /// parse parses the puzzle input from a string, like the contents of an input file
+    /// or a sample embedded with `include_str!`.
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    /// part1 returns the answer to the first part of the puzzle, or an error if the input is
+    /// well-formed but has no answer.
+    fn part1(input: &Self::Input) -> Result<Self::Part1>;
+
+    /// part2 returns the answer to the second part of the puzzle, or an error if the input is
+    /// well-formed but has no answer.
+    fn part2(input: &Self::Input) -> Result<Self::Part2>;
+
+    /// read parses the puzzle input from a reader, like stdin.
+    fn read(mut reader: impl BufRead) -> Result<Self::Input> {
+        let mut input = String::new();
+        reader.read_to_string(&mut input)?;
+
+        Self::parse(&input)
+    }
+
+    /// load parses the puzzle input in the given file.
+    fn load(filename: &str) -> Result<Self::Input> {
+        File::open(filename)
+            .map_err(Into::into)
+            .and_then(|f| Self::read(BufReader::new(f)))
+            .map_err(|err| err.in_file(filename))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day1::Day1;
+    use crate::error::Error;
+
+    use super::*;
+
+    #[test]
+    fn test_read() {
+        let elves = Day1::read(include_str!("../input/day1_sample.txt").as_bytes()).unwrap();
+
+        assert_eq!(24000, Day1::part1(&elves).unwrap());
+    }
+
+    #[test]
+    fn test_load() {
+        let elves = Day1::load("input/day1_sample.txt").unwrap();
+        assert_eq!(24000, Day1::part1(&elves).unwrap());
+
+        let err = Day1::load("input/day1_missing.txt").err().unwrap();
+        assert!(matches!(err, Error::Io { file: Some(ref file), .. } if file == "input/day1_missing.txt"));
+    }
+}